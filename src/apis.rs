@@ -0,0 +1,98 @@
+//! The shared request layer: every call to `/apis/*` goes through here so that it carries the
+//! session token, and an expired session sends the user back to the login page.
+
+use yew::agent::*;
+use yew::callback::Callback;
+use yew::format::{Binary, Text};
+use yew::services::fetch::*;
+use yew::services::storage::{Area, StorageService};
+use yew_router::agent::{RouteAgentDispatcher, RouteRequest};
+use anyhow::Error;
+use crate::query;
+use crate::route::Route;
+use crate::session::{SessionAgent, SessionRequest};
+
+const TOKEN_KEY: &str = "jian_ai.token";
+
+pub fn token() -> Option<String> {
+    StorageService::new(Area::Local).ok()
+        .and_then(|storage| storage.restore::<Text>(TOKEN_KEY).ok())
+}
+
+pub fn set_token(token: Option<String>) {
+    if let Ok(mut storage) = StorageService::new(Area::Local) {
+        match token {
+            Some(token) => storage.store(TOKEN_KEY, Ok(token)),
+            None => storage.remove(TOKEN_KEY)
+        }
+    }
+}
+
+fn authorized(builder: http::request::Builder) -> http::request::Builder {
+    match token() {
+        Some(token) => builder.header("Authorization", format!("Bearer {}", token)),
+        None => builder
+    }
+}
+
+pub fn get<T: AsRef<str>>(uri: T) -> http::request::Builder {
+    authorized(Request::get(uri.as_ref()))
+}
+
+pub fn post<T: AsRef<str>>(uri: T) -> http::request::Builder {
+    authorized(Request::post(uri.as_ref()))
+}
+
+/// Posts to `uri` while the page unloads. Unlike the requests of `fetch`, it is not cancelled
+/// then, and unlike a beacon it carries the session token.
+pub fn post_on_unload(uri: &str) {
+    let headers = js_sys::Object::new();
+    if let Some(token) = token() {
+        let _ = js_sys::Reflect::set(&headers, &"Authorization".into(), &format!("Bearer {}", token).into());
+    }
+    let mut init = web_sys::RequestInit::new();
+    init.method("POST").headers(&headers);
+    let _ = js_sys::Reflect::set(&init, &"keepalive".into(), &true.into());
+    let _ = yew::utils::window().fetch_with_str_and_init(uri, &init);
+}
+
+/// Like `FetchService::fetch`, but a 401 ends the session before the callback sees the response.
+pub fn fetch<IN, OUT>(request: Request<IN>, callback: Callback<Response<OUT>>) -> Result<FetchTask, Error>
+where
+    IN: Into<Text>,
+    OUT: From<Text> + 'static
+{
+    FetchService::fetch(request, guarded(callback))
+}
+
+/// Like `fetch`, for responses that are not text, such as the photos themselves.
+pub fn fetch_binary<IN, OUT>(request: Request<IN>, callback: Callback<Response<OUT>>) -> Result<FetchTask, Error>
+where
+    IN: Into<Binary>,
+    OUT: From<Binary> + 'static
+{
+    FetchService::fetch_binary(request, guarded(callback))
+}
+
+fn guarded<OUT: 'static>(callback: Callback<Response<OUT>>) -> Callback<Response<OUT>> {
+    Callback::from(move |response: Response<OUT>| {
+        if response.status() == StatusCode::UNAUTHORIZED {
+            unauthorized();
+        }
+        callback.emit(response);
+    })
+}
+
+/// Forgets the token and goes to the login page, which comes back here once logged in.
+fn unauthorized() {
+    set_token(None);
+    SessionAgent::dispatcher().send(SessionRequest::Expired);
+    let location = yew::utils::window().location();
+    let path = location.pathname().unwrap_or_default();
+    let login = yew_router::route::Route::<()>::from(Route::LoginPage).route;
+    if path != login {
+        let next = path + &location.search().unwrap_or_default();
+        let route = yew_router::route::Route::new_no_state(login + &query::build(&[("next", next)]));
+        RouteAgentDispatcher::<()>::new().send(RouteRequest::ChangeRoute(route));
+    }
+}
@@ -1,25 +1,76 @@
 use yew::prelude::*;
 use yew_router::prelude::*;
 use crate::pages::*;
+use yew_router::switch::Permissive;
+use percent_encoding::percent_decode_str;
+use crate::components::{Layout, Redirect};
+use crate::event_buses::LocaleEventBus;
+use crate::i18n::{self, Locale};
 use crate::route::Route;
+use crate::session::*;
 
-pub struct App {}
+pub struct App {
+    session: Session,
+    _event_bus_locale: Box<dyn Bridge<LocaleEventBus>>,
+    _session: Box<dyn Bridge<SessionAgent>>
+}
+
+pub enum Msg {
+    Locale(Locale),
+    Session(Session)
+}
 
 impl Component for App {
-    type Message = ();
+    type Message = Msg;
     type Properties = ();
 
-    fn create(_: Self::Properties, _link: ComponentLink<Self>) -> Self { Self {} }
-    fn update(&mut self, _msg: Self::Message) -> ShouldRender { true }
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        i18n::init();
+        Self {
+            session: Session::Pending,
+            _event_bus_locale: LocaleEventBus::bridge(link.callback(Msg::Locale)),
+            _session: SessionAgent::bridge(link.callback(Msg::Session))
+        }
+    }
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            // Rendering everything again picks up the strings of a newly chosen locale.
+            Msg::Locale(_) => {}
+            Msg::Session(session) => { self.session = session }
+        }
+        true
+    }
     fn change(&mut self, _: Self::Properties) -> ShouldRender { false }
     fn view(&self) -> Html {
-        let render = Router::render(|switch: Route| match switch {
-            Route::TaggingPage => html! {<tagging::TaggingPage />},
-            Route::NewTagPage => html! {<new_tag::NewTagPage />}
+        let session = self.session.clone();
+        let render = Router::render(move |switch: Route| html! {
+            <Layout route=switch.clone()>
+                {match switch {
+                    _ if switch.allowed(&session) => html! {},
+                    _ if session == Session::Pending => html! {},
+                    _ if session == Session::LoggedOut => html! {<Redirect to=Route::LoginPage />},
+                    _ => html! {<section><article><p>{i18n::t("errors.forbidden")}</p></article></section>}
+                }}
+                {match switch {
+                    _ if !switch.allowed(&session) => html! {},
+                    Route::Home => html! {<Redirect to=Route::TaggingPage />},
+                    Route::TaggingPage => html! {<tagging::TaggingPage />},
+                    Route::TaggingPhoto(filename) if filename.is_empty() => html! {<Redirect to=Route::TaggingPage />},
+                    Route::TaggingPhoto(filename) => html! {<tagging::TaggingPage photo=percent_decode_str(&filename).decode_utf8_lossy().to_string() />},
+                    Route::NewTagPage => html! {<new_tag::NewTagPage />},
+                    Route::StatsPage => html! {<stats::StatsPage />},
+                    Route::SkippedPage => html! {<skipped::SkippedPage />},
+                    Route::HiddenPage => html! {<hidden::HiddenPage />},
+                    Route::ExportPage => html! {<export::ExportPage />},
+                    Route::LoginPage => html! {<login::LoginPage />},
+                    Route::NotFound(Permissive(path)) => html! {<not_found::NotFoundPage path=path />}
+                }}
+            </Layout>
         });
+        let redirect = Router::redirect(|route: yew_router::route::Route| Route::NotFound(Permissive(Some(route.route))));
 
         html! {
-            <Router<Route, ()> render=render/>
+            <Router<Route, ()> render=render redirect=redirect/>
         }
     }
 }
@@ -0,0 +1,82 @@
+//! Bursts of photos taken moments apart at the same place, which mostly show the same people.
+
+use std::collections::HashMap;
+
+/// Photos taken at most this many seconds apart belong to the same burst.
+const MAX_GAP_SECS: u64 = 60;
+/// A burst ends after this long or this many photos, however closely they follow each other,
+/// so that a whole event shot without a pause does not become one burst.
+const MAX_SPAN_SECS: u64 = 5 * 60;
+const MAX_PHOTOS: usize = 20;
+
+fn folder(photo: &str) -> &str {
+    photo.rsplit_once('/').map_or("", |(folder, _)| folder)
+}
+
+/// Splits a name like `IMG_1234.JPG` into `IMG_` and 1234.
+fn sequence(photo: &str) -> Option<(&str, u64)> {
+    let name = photo.rsplit('/').next().unwrap_or(photo);
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    let digits = stem.len() - stem.chars().rev().take_while(|c| c.is_ascii_digit()).count();
+    let (prefix, number) = stem.split_at(digits);
+    number.parse().ok().map(|number| (prefix, number))
+}
+
+/// Chains the photos of each folder in the order they were taken, and cuts the chain wherever
+/// two neighbours are too far apart or the burst has grown too long. File numbers only order
+/// photos taken within the same second, as numbering alone says nothing of how far apart they
+/// were taken. Photos of unknown time and single photos are left out.
+pub fn clusters(photos: &[String], taken: &HashMap<String, u64>) -> Vec<Vec<String>> {
+    let mut ordered = photos.iter().filter_map(|photo| taken.get(photo).map(|at| (photo, *at))).collect::<Vec<_>>();
+    ordered.sort_by_key(|(photo, at)| (folder(photo), *at, sequence(photo), *photo));
+    let mut clusters: Vec<Vec<String>> = vec![];
+    let mut current: Vec<(&String, u64)> = vec![];
+    for (photo, at) in ordered {
+        if let (Some((first, start)), Some((_, last))) = (current.first(), current.last()) {
+            if folder(first) != folder(photo) || at - last > MAX_GAP_SECS || at - start > MAX_SPAN_SECS || current.len() == MAX_PHOTOS {
+                clusters.push(current.drain(..).map(|(photo, _)| photo.clone()).collect());
+            }
+        }
+        current.push((photo, at));
+    }
+    clusters.push(current.into_iter().map(|(photo, _)| photo.clone()).collect());
+    clusters.into_iter().filter(|cluster| cluster.len() > 1).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn photos(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn taken(times: &[(&str, u64)]) -> HashMap<String, u64> {
+        times.iter().map(|(photo, at)| (photo.to_string(), *at)).collect()
+    }
+
+    #[test]
+    fn file_numbers_alone_make_no_bursts() {
+        let list = (1..=500).map(|i| format!("event/IMG_{:04}.JPG", i)).collect::<Vec<_>>();
+        assert!(clusters(&list, &HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn splits_on_gaps_and_folders() {
+        let list = photos(&["a/1.jpg", "a/2.jpg", "a/3.jpg", "b/4.jpg"]);
+        let times = taken(&[("a/1.jpg", 0), ("a/2.jpg", 30), ("a/3.jpg", 200), ("b/4.jpg", 31)]);
+        assert_eq!(clusters(&list, &times), vec![photos(&["a/1.jpg", "a/2.jpg"])]);
+    }
+
+    #[test]
+    fn caps_the_length_of_a_burst() {
+        let list = (0..100).map(|i| format!("event/{}.jpg", i)).collect::<Vec<_>>();
+        let times = list.iter().enumerate().map(|(i, photo)| (photo.clone(), i as u64)).collect();
+        let bursts = clusters(&list, &times);
+        assert_eq!(bursts.len(), 5);
+        assert!(bursts.iter().all(|burst| burst.len() == MAX_PHOTOS));
+
+        let times = list.iter().enumerate().map(|(i, photo)| (photo.clone(), i as u64 * 50)).collect();
+        assert!(clusters(&list, &times).iter().all(|burst| burst.len() <= (MAX_SPAN_SECS / 50 + 1) as usize));
+    }
+}
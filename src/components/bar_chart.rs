@@ -0,0 +1,52 @@
+use yew::prelude::*;
+use crate::i18n::number;
+
+const BAR_HEIGHT: f64 = 20.0;
+const BAR_GAP: f64 = 4.0;
+const LABEL_WIDTH: f64 = 120.0;
+const VALUE_WIDTH: f64 = 60.0;
+const CHART_WIDTH: f64 = 600.0;
+
+/// A horizontal bar chart rendered as SVG.
+pub struct BarChart {
+    props: Props
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    /// Label and value of every bar, drawn top to bottom.
+    pub bars: Vec<(String, f64)>,
+    /// How a value is printed at the end of its bar.
+    #[prop_or(1)]
+    pub precision: usize
+}
+
+impl Component for BarChart {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self { Self { props } }
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender { false }
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+    fn view(&self) -> Html {
+        let max = self.props.bars.iter().map(|(_, v)| *v).fold(0.0, f64::max);
+        let scale = if max > 0.0 { (CHART_WIDTH - LABEL_WIDTH - VALUE_WIDTH) / max } else { 0.0 };
+        let height = self.props.bars.len() as f64 * (BAR_HEIGHT + BAR_GAP);
+        html! {
+            <svg class="bar-chart" width={CHART_WIDTH.to_string()} height={height.to_string()} viewBox={format!("0 0 {} {}", CHART_WIDTH, height)}>
+                {self.props.bars.iter().enumerate().map(|(i, (label, value))| {
+                    let y = i as f64 * (BAR_HEIGHT + BAR_GAP);
+                    let width = value * scale;
+                    html! {<g>
+                        <text x={(LABEL_WIDTH - 4.0).to_string()} y={(y + BAR_HEIGHT * 0.75).to_string()} text-anchor="end">{label}</text>
+                        <rect x={LABEL_WIDTH.to_string()} y={y.to_string()} width={width.to_string()} height={BAR_HEIGHT.to_string()} />
+                        <text x={(LABEL_WIDTH + width + 4.0).to_string()} y={(y + BAR_HEIGHT * 0.75).to_string()}>{number(*value, self.props.precision)}</text>
+                    </g>}
+                }).collect::<Html>()}
+            </svg>
+        }
+    }
+}
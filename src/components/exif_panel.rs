@@ -0,0 +1,128 @@
+use yew::prelude::*;
+use yew::services::fetch::*;
+use yew::format::*;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use crate::apis;
+use crate::i18n::{number, t};
+use crate::metadata::{self, Metadata};
+use crate::route::encode_path;
+
+/// The EXIF data sits at the start of the file, so there is no need for the whole photo.
+const HEAD_BYTES: usize = 128 * 1024;
+
+/// A side panel with when, where and with what the photo was taken.
+pub struct ExifPanel {
+    state: State,
+    props: Props,
+    link: ComponentLink<Self>,
+    _task: Option<FetchTask>
+}
+
+struct State {
+    loading: bool,
+    metadata: Option<Metadata>
+}
+
+pub enum Msg {
+    Refresh,
+    GetMetadataOk(Metadata),
+    GetMetadataErr
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub photo: String,
+    #[prop_or_default]
+    pub onmetadata: Callback<Metadata>
+}
+
+fn row(label: &str, value: Option<String>) -> Html {
+    html! {<>
+        <dt>{t(label)}</dt>
+        <dd>{value.unwrap_or_else(|| "—".to_string())}</dd>
+    </>}
+}
+
+impl Component for ExifPanel {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(Msg::Refresh);
+        Self {
+            state: State {
+                loading: false,
+                metadata: None
+            },
+            props,
+            link,
+            _task: None
+        }
+    }
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Refresh => {
+                self.state.loading = true;
+                self.state.metadata = None;
+                let req = apis::get(format!("/pics/{}", encode_path(&self.props.photo)))
+                    .header("Range", format!("bytes=0-{}", HEAD_BYTES - 1))
+                    .body(Nothing).unwrap();
+                let on_done = self.link.callback(move |response: Response<Binary>| {
+                    match response.into_body() {
+                        Ok(bytes) => metadata::parse(&bytes).map(Msg::GetMetadataOk).unwrap_or(Msg::GetMetadataErr),
+                        Err(_) => Msg::GetMetadataErr
+                    }
+                });
+                self._task = apis::fetch_binary(req, on_done).ok();
+                true
+            }
+            Msg::GetMetadataOk(metadata) => {
+                self.state.loading = false;
+                self.props.onmetadata.emit(metadata.clone());
+                self.state.metadata = Some(metadata);
+                true
+            }
+            Msg::GetMetadataErr => {
+                self.state.loading = false;
+                true
+            }
+        }
+    }
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let reload = props.photo != self.props.photo;
+        self.props = props;
+        if reload {
+            self.link.send_message(Msg::Refresh);
+        }
+        // Rendered again even for the same photo, to pick up the strings of a new locale.
+        true
+    }
+    fn view(&self) -> Html {
+        html! {
+            <aside class="exif">
+                <h3>{t("exif.title")}</h3>
+                {if self.state.loading {
+                    html! {<p>{t("exif.loading")}</p>}
+                } else if let Some(metadata) = &self.state.metadata {
+                    html! {<dl>
+                        {row("exif.taken_at", metadata.taken_at.clone())}
+                        {row("exif.camera", metadata.camera.clone())}
+                        {row("exif.dimensions", metadata.dimensions.map(|(w, h)| format!("{} × {}", number(w as f64, 0), number(h as f64, 0))))}
+                        {row("exif.orientation", metadata.orientation.map(|o| o.to_string()))}
+                        <dt>{t("exif.location")}</dt>
+                        <dd>{if let Some((lat, lon)) = metadata.gps {
+                            let query = utf8_percent_encode(&format!("{:.6},{:.6}", lat, lon), NON_ALPHANUMERIC).to_string();
+                            html! {<a href=format!("https://www.openstreetmap.org/search?query={}", query) target="_blank" rel="noopener">
+                                {format!("{}, {}", number(lat, 5), number(lon, 5))}
+                            </a>}
+                        } else {
+                            html! {"—"}
+                        }}</dd>
+                    </dl>}
+                } else {
+                    html! {<p>{t("exif.none")}</p>}
+                }}
+            </aside>
+        }
+    }
+}
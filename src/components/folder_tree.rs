@@ -0,0 +1,150 @@
+use std::collections::{BTreeMap, HashSet};
+use std::rc::Rc;
+use yew::prelude::*;
+use crate::i18n::{number, t};
+
+/// The folders of a list of photo paths, with how many photos each holds, subfolders included.
+pub struct FolderTree {
+    state: State,
+    props: Props,
+    link: ComponentLink<Self>
+}
+
+struct State {
+    root: Folder,
+    /// The paths of the folders showing their subfolders.
+    expanded: HashSet<String>
+}
+
+#[derive(Default)]
+struct Folder {
+    count: usize,
+    children: BTreeMap<String, Folder>
+}
+
+impl Folder {
+    fn build(photos: &[String]) -> Self {
+        let mut root = Folder::default();
+        for photo in photos {
+            root.count += 1;
+            let mut folder = &mut root;
+            let mut parts = photo.split('/').collect::<Vec<_>>();
+            // The last part is the file itself.
+            parts.pop();
+            for part in parts.into_iter().filter(|part| !part.is_empty()) {
+                folder = folder.children.entry(part.to_string()).or_default();
+                folder.count += 1;
+            }
+        }
+        root
+    }
+}
+
+pub enum Msg {
+    Toggle(String),
+    Select(String)
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    /// A new list is told apart from the old one by identity, not by comparing every path.
+    pub photos: Rc<Vec<String>>,
+    /// The path of the chosen folder, empty for all of them.
+    #[prop_or_default]
+    pub selected: String,
+    pub onselect: Callback<String>
+}
+
+impl FolderTree {
+    /// Opens the folders down to the chosen one, so that it can be seen.
+    fn expand_selected(&mut self) {
+        let mut path = String::new();
+        for part in self.props.selected.trim_matches('/').split('/').filter(|part| !part.is_empty()) {
+            if !path.is_empty() {
+                path.push('/');
+            }
+            path.push_str(part);
+            self.state.expanded.insert(path.clone());
+        }
+    }
+
+    fn view_folder(&self, name: &str, path: String, folder: &Folder) -> Html {
+        let expanded = self.state.expanded.contains(&path);
+        let selected = self.props.selected.trim_matches('/') == path;
+        let toggle_path = path.clone();
+        let select_path = path.clone();
+        html! {
+            <li class=classes!(selected.then_some("selected"))>
+                {if folder.children.is_empty() {
+                    html! {<span class="toggle"></span>}
+                } else {
+                    html! {<span class="toggle" onclick=self.link.callback(move |_| Msg::Toggle(toggle_path.clone()))>{if expanded { "▾" } else { "▸" }}</span>}
+                }}
+                <a onclick=self.link.callback(move |_| Msg::Select(select_path.clone()))>
+                    {name}
+                    <span class="count">{number(folder.count as f64, 0)}</span>
+                </a>
+                {if expanded && !folder.children.is_empty() {
+                    html! {<ul>{folder.children.iter().map(|(child, sub)| {
+                        let child_path = if path.is_empty() { child.clone() } else { format!("{}/{}", path, child) };
+                        self.view_folder(child, child_path, sub)
+                    }).collect::<Html>()}</ul>}
+                } else {
+                    html! {}
+                }}
+            </li>
+        }
+    }
+}
+
+impl Component for FolderTree {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut tree = Self {
+            state: State {
+                root: Folder::build(&props.photos),
+                // The top level folders are shown from the start.
+                expanded: std::iter::once(String::new()).collect()
+            },
+            props,
+            link
+        };
+        tree.expand_selected();
+        tree
+    }
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Toggle(path) => {
+                if !self.state.expanded.remove(&path) {
+                    self.state.expanded.insert(path);
+                }
+                true
+            }
+            Msg::Select(path) => {
+                self.props.onselect.emit(path);
+                false
+            }
+        }
+    }
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if !Rc::ptr_eq(&props.photos, &self.props.photos) {
+            self.state.root = Folder::build(&props.photos);
+        }
+        // Only a newly chosen folder is opened, so that closing one above it sticks.
+        let selected_changed = props.selected != self.props.selected;
+        self.props = props;
+        if selected_changed {
+            self.expand_selected();
+        }
+        true
+    }
+    fn view(&self) -> Html {
+        html! {
+            <ul class="folder-tree">
+                {self.view_folder(&t("folder_tree.all"), String::new(), &self.state.root)}
+            </ul>
+        }
+    }
+}
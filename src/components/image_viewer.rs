@@ -0,0 +1,283 @@
+use yew::prelude::*;
+use yew::services::resize::{ResizeService, ResizeTask};
+use yew::web_sys::{Element, HtmlImageElement};
+use crate::i18n::{number, t};
+
+/// How much one wheel notch or key press zooms.
+const ZOOM_STEP: f64 = 1.25;
+const MIN_SCALE: f64 = 0.05;
+const MAX_SCALE: f64 = 16.0;
+/// How far the arrow keys pan, in screen pixels.
+const PAN_STEP: f64 = 50.0;
+
+/// Shows an image that can be zoomed and panned. The children are laid over the image and move
+/// with it, so overlays given in image pixels stay on what they point at.
+pub struct ImageViewer {
+    state: State,
+    props: Props,
+    link: ComponentLink<Self>,
+    container_ref: NodeRef,
+    image_ref: NodeRef,
+    _resize: ResizeTask
+}
+
+struct State {
+    scale: f64,
+    /// Where the top left corner of the image is in the viewer.
+    x: f64,
+    y: f64,
+    /// Whether the image is fitted to the viewer again as the window resizes, until zoomed or
+    /// panned by hand.
+    fit: bool,
+    /// The pointer position a drag continues from.
+    drag: Option<(f64, f64)>,
+    /// The distance between two fingers a pinch continues from.
+    pinch: Option<f64>
+}
+
+pub enum Msg {
+    Loaded,
+    Fit,
+    Resize,
+    ActualSize,
+    /// Zooms by a factor, keeping the given point of the viewer in place.
+    Zoom(f64, Option<(f64, f64)>),
+    Pan(f64, f64),
+    Wheel(WheelEvent),
+    DragStart(f64, f64),
+    DragMove(f64, f64),
+    DragEnd,
+    Touch(TouchEvent),
+    Key(KeyboardEvent)
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub src: String,
+    /// Clockwise degrees to turn the image by, a multiple of 90.
+    #[prop_or_default]
+    pub rotation: u32,
+    /// Whether to mirror the image horizontally before turning it.
+    #[prop_or_default]
+    pub mirrored: bool,
+    #[prop_or_default]
+    pub children: Children,
+    #[prop_or_default]
+    pub onerror: Callback<()>
+}
+
+impl ImageViewer {
+    fn viewport(&self) -> (f64, f64) {
+        self.container_ref.cast::<Element>()
+            .map(|e| (e.client_width() as f64, e.client_height() as f64))
+            .unwrap_or((0.0, 0.0))
+    }
+
+    fn natural_size(&self) -> Option<(f64, f64)> {
+        self.image_ref.cast::<HtmlImageElement>()
+            .filter(|img| img.natural_width() > 0)
+            .map(|img| (img.natural_width() as f64, img.natural_height() as f64))
+    }
+
+    /// The size of the image as shown, that is after turning it.
+    fn shown_size(&self) -> Option<(f64, f64)> {
+        self.natural_size().map(|(w, h)| if self.props.rotation % 180 == 0 { (w, h) } else { (h, w) })
+    }
+
+    /// Converts a position in the page into one in the viewer.
+    fn local(&self, client_x: f64, client_y: f64) -> (f64, f64) {
+        match self.container_ref.cast::<Element>() {
+            Some(e) => {
+                let rect = e.get_bounding_client_rect();
+                (client_x - rect.left(), client_y - rect.top())
+            }
+            None => (client_x, client_y)
+        }
+    }
+
+    fn fit(&mut self) {
+        let ((vw, vh), Some((w, h))) = (self.viewport(), self.shown_size()) else { return };
+        // Small images are not blown up, since that only makes them blurry.
+        self.state.scale = (vw / w).min(vh / h).min(1.0);
+        self.state.x = (vw - w * self.state.scale) / 2.0;
+        self.state.y = (vh - h * self.state.scale) / 2.0;
+        self.state.fit = true;
+    }
+
+    fn zoom(&mut self, factor: f64, at: Option<(f64, f64)>) {
+        let (vw, vh) = self.viewport();
+        let (px, py) = at.unwrap_or((vw / 2.0, vh / 2.0));
+        let scale = (self.state.scale * factor).max(MIN_SCALE).min(MAX_SCALE);
+        let ratio = scale / self.state.scale;
+        self.state.x = px - (px - self.state.x) * ratio;
+        self.state.y = py - (py - self.state.y) * ratio;
+        self.state.scale = scale;
+        self.state.fit = false;
+    }
+
+    fn touch_points(&self, e: &TouchEvent) -> Vec<(f64, f64)> {
+        let touches = e.touches();
+        (0..touches.length())
+            .filter_map(|i| touches.get(i))
+            .map(|t| self.local(t.client_x() as f64, t.client_y() as f64))
+            .collect()
+    }
+}
+
+impl Component for ImageViewer {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            state: State {
+                scale: 1.0,
+                x: 0.0,
+                y: 0.0,
+                fit: true,
+                drag: None,
+                pinch: None
+            },
+            props,
+            _resize: ResizeService::register(link.callback(|_| Msg::Resize)),
+            link,
+            container_ref: NodeRef::default(),
+            image_ref: NodeRef::default()
+        }
+    }
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Loaded | Msg::Fit => self.fit(),
+            Msg::Resize if self.state.fit => self.fit(),
+            Msg::Resize => return false,
+            Msg::ActualSize => {
+                let (vw, vh) = self.viewport();
+                let (w, h) = self.shown_size().unwrap_or((0.0, 0.0));
+                self.state.scale = 1.0;
+                self.state.x = (vw - w) / 2.0;
+                self.state.y = (vh - h) / 2.0;
+                self.state.fit = false;
+            }
+            Msg::Zoom(factor, at) => self.zoom(factor, at),
+            Msg::Pan(dx, dy) => {
+                self.state.x += dx;
+                self.state.y += dy;
+                self.state.fit = false;
+            }
+            Msg::Wheel(e) => {
+                e.prevent_default();
+                let factor = if e.delta_y() < 0.0 { ZOOM_STEP } else { 1.0 / ZOOM_STEP };
+                let at = self.local(e.client_x() as f64, e.client_y() as f64);
+                self.zoom(factor, Some(at));
+            }
+            Msg::DragStart(x, y) => {
+                self.state.drag = Some((x, y));
+                return false;
+            }
+            Msg::DragMove(x, y) => {
+                let Some((from_x, from_y)) = self.state.drag else { return false };
+                self.state.x += x - from_x;
+                self.state.y += y - from_y;
+                self.state.drag = Some((x, y));
+                self.state.fit = false;
+            }
+            Msg::DragEnd => {
+                self.state.drag = None;
+                self.state.pinch = None;
+                return false;
+            }
+            Msg::Touch(e) => {
+                let points = self.touch_points(&e);
+                match points.as_slice() {
+                    [(x, y)] => {
+                        e.prevent_default();
+                        self.state.pinch = None;
+                        if let Some((from_x, from_y)) = self.state.drag {
+                            self.state.x += x - from_x;
+                            self.state.y += y - from_y;
+                            self.state.fit = false;
+                        }
+                        self.state.drag = Some((*x, *y));
+                    }
+                    [(x1, y1), (x2, y2), ..] => {
+                        e.prevent_default();
+                        self.state.drag = None;
+                        let distance = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+                        if let Some(from) = self.state.pinch.filter(|from| *from > 0.0) {
+                            self.zoom(distance / from, Some(((x1 + x2) / 2.0, (y1 + y2) / 2.0)));
+                        }
+                        self.state.pinch = Some(distance);
+                    }
+                    [] => {
+                        self.state.drag = None;
+                        self.state.pinch = None;
+                    }
+                }
+            }
+            Msg::Key(e) => {
+                match e.key().as_str() {
+                    "+" | "=" => self.zoom(ZOOM_STEP, None),
+                    "-" => self.zoom(1.0 / ZOOM_STEP, None),
+                    "0" => self.fit(),
+                    "1" => self.link.send_message(Msg::ActualSize),
+                    "ArrowLeft" => self.link.send_message(Msg::Pan(PAN_STEP, 0.0)),
+                    "ArrowRight" => self.link.send_message(Msg::Pan(-PAN_STEP, 0.0)),
+                    "ArrowUp" => self.link.send_message(Msg::Pan(0.0, PAN_STEP)),
+                    "ArrowDown" => self.link.send_message(Msg::Pan(0.0, -PAN_STEP)),
+                    _ => return false
+                }
+                e.prevent_default();
+            }
+        }
+        true
+    }
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let src_changed = props.src != self.props.src;
+        let rotation_changed = props.rotation != self.props.rotation;
+        self.props = props;
+        if src_changed {
+            // The new image is fitted once it has loaded.
+            self.state.fit = true;
+        } else if rotation_changed && self.state.fit {
+            self.fit();
+        }
+        true
+    }
+    fn view(&self) -> Html {
+        let style = format!("transform: translate({}px, {}px) scale({}); transform-origin: 0 0;", self.state.x, self.state.y, self.state.scale);
+        let classes = classes!("image-viewer", self.state.drag.is_some().then_some("dragging"));
+        // The image keeps its own pixels, turned around the middle of the box it ends up in, so
+        // the overlays given in image pixels turn with it.
+        let (w, h) = self.natural_size().unwrap_or((0.0, 0.0));
+        let (sw, sh) = self.shown_size().unwrap_or((0.0, 0.0));
+        let turn = format!("position: absolute; transform-origin: 0 0; transform: translate({}px, {}px) rotate({}deg) scaleX({}) translate({}px, {}px);",
+            sw / 2.0, sh / 2.0, self.props.rotation, if self.props.mirrored { -1 } else { 1 }, -w / 2.0, -h / 2.0);
+        html! {
+            <div ref=self.container_ref.clone() class=classes tabindex="0"
+                onwheel=self.link.callback(Msg::Wheel)
+                onmousedown=self.link.callback(|e: MouseEvent| Msg::DragStart(e.client_x() as f64, e.client_y() as f64))
+                onmousemove=self.link.callback(|e: MouseEvent| Msg::DragMove(e.client_x() as f64, e.client_y() as f64))
+                onmouseup=self.link.callback(|_| Msg::DragEnd)
+                onmouseleave=self.link.callback(|_| Msg::DragEnd)
+                ontouchstart=self.link.callback(Msg::Touch)
+                ontouchmove=self.link.callback(Msg::Touch)
+                ontouchend=self.link.callback(Msg::Touch)
+                onkeydown=self.link.callback(Msg::Key)>
+                <div class="image-viewer-content" style=style>
+                    <div style=turn>
+                        // The orientation is applied above, so the browser must not apply it again.
+                        <img ref=self.image_ref.clone() src=self.props.src.clone() style="image-orientation: none;" draggable="false" onload=self.link.callback(|_| Msg::Loaded) onerror=self.props.onerror.reform(|_| ()) />
+                        <div class="image-viewer-overlay">{self.props.children.clone()}</div>
+                    </div>
+                </div>
+                <div class="image-viewer-controls">
+                    <button type="button" class=classes!(self.state.fit.then_some("active")) onclick=self.link.callback(|_| Msg::Fit)>{t("image_viewer.fit")}</button>
+                    <button type="button" onclick=self.link.callback(|_| Msg::ActualSize)>{"100%"}</button>
+                    <button type="button" onclick=self.link.callback(|_| Msg::Zoom(ZOOM_STEP, None))>{"+"}</button>
+                    <button type="button" onclick=self.link.callback(|_| Msg::Zoom(1.0 / ZOOM_STEP, None))>{"−"}</button>
+                    <span>{format!("{}%", number(self.state.scale * 100.0, 0))}</span>
+                </div>
+            </div>
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::time::Duration;
+use yew::prelude::*;
+use yew::agent::*;
+use yew::services::timeout::*;
+use yew::services::fetch::*;
+use yew::format::*;
+use anyhow::Error;
+use yew_router::agent::RouteRequest;
+use yew_router::prelude::*;
+use crate::apis;
+use crate::components::SessionStats;
+use crate::i18n::{self, t, tn, Locale};
+use crate::event_buses::*;
+use crate::route::Route;
+use crate::save_queue::*;
+use crate::session::*;
+
+const NOTIFICATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The frame around every page: header, navigation, footer and the global widgets.
+pub struct Layout {
+    state: State,
+    props: Props,
+    link: ComponentLink<Self>,
+    save_queue: Box<dyn Bridge<SaveQueue>>,
+    event_bus_locale: Dispatcher<LocaleEventBus>,
+    _event_bus_notification: Box<dyn Bridge<NotificationEventBus>>,
+    session: Box<dyn Bridge<SessionAgent>>,
+    router: RouteAgentDispatcher,
+    _logout_task: Option<FetchTask>
+}
+
+struct State {
+    session: Session,
+    doing: usize,
+    failed: usize,
+    next_notification_id: usize,
+    notifications: Vec<(usize, Notification, TimeoutTask)>
+}
+
+pub enum Msg {
+    SaveQueue(SaveQueueOutput),
+    RetryFailed,
+    UILocaleState(ChangeData),
+    Session(Session),
+    Logout,
+    Notify(Notification),
+    Dismiss(usize)
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub route: Route,
+    #[prop_or_default]
+    pub children: Children
+}
+
+impl Layout {
+    /// The navigation entry the current route belongs to.
+    fn section(&self) -> Route {
+        match &self.props.route {
+            Route::TaggingPhoto(_) => Route::TaggingPage,
+            route => route.clone()
+        }
+    }
+
+    fn nav_item(&self, route: Route, text: String) -> Html {
+        if !route.allowed(&self.state.session) {
+            return html! {};
+        }
+        let classes = if route == self.section() { "active" } else { "" };
+        html! {<li><RouterAnchor<Route> route=route classes=classes>{text}</RouterAnchor<Route>></li>}
+    }
+}
+
+impl Component for Layout {
+    type Message = Msg;
+    type Properties = Props;
+
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            state: State {
+                session: Session::Pending,
+                doing: 0,
+                failed: 0,
+                next_notification_id: 0,
+                notifications: vec![]
+            },
+            props,
+            save_queue: SaveQueue::bridge(link.callback(Msg::SaveQueue)),
+            event_bus_locale: LocaleEventBus::dispatcher(),
+            _event_bus_notification: NotificationEventBus::bridge(link.callback(Msg::Notify)),
+            session: SessionAgent::bridge(link.callback(Msg::Session)),
+            router: RouteAgentDispatcher::new(),
+            _logout_task: None,
+            link
+        }
+    }
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::SaveQueue(SaveQueueOutput::Pending { doing, failed }) => {
+                self.state.doing = doing;
+                self.state.failed = failed;
+                true
+            }
+            Msg::SaveQueue(_) => { false }
+            Msg::RetryFailed => {
+                self.save_queue.send(SaveQueueRequest::RetryFailed);
+                false
+            }
+            Msg::UILocaleState(v) => {
+                if let Some(locale) = match v {
+                    ChangeData::Select(select) => Locale::from_code(&select.value()),
+                    _ => None
+                } {
+                    i18n::set_locale(locale);
+                    self.event_bus_locale.send(locale);
+                }
+                false
+            }
+            Msg::Session(session) => {
+                self.state.session = session;
+                true
+            }
+            Msg::Logout => {
+                // The token is dropped locally either way, so the outcome of the request does not matter.
+                let req = apis::post("/apis/logout").body(Nothing).unwrap();
+                let on_done: Callback<Response<Result<String, Error>>> = Callback::noop();
+                self._logout_task = apis::fetch(req, on_done).ok();
+                apis::set_token(None);
+                self.session.send(SessionRequest::LoggedOut);
+                self.router.send(RouteRequest::ChangeRoute(Route::LoginPage.into()));
+                false
+            }
+            Msg::Notify(notification) => {
+                let id = self.state.next_notification_id;
+                self.state.next_notification_id += 1;
+                let task = TimeoutService::spawn(NOTIFICATION_TIMEOUT, self.link.callback(move |_| Msg::Dismiss(id)));
+                self.state.notifications.push((id, notification, task));
+                true
+            }
+            Msg::Dismiss(id) => {
+                self.state.notifications.retain(|(i, _, _)| *i != id);
+                true
+            }
+        }
+    }
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+    fn view(&self) -> Html {html!{<>
+<header>
+    <h1>{t("app.title")}</h1>
+    {if self.section() == Route::TaggingPage { html!{<SessionStats />} } else { html!{} }}
+    <div class="user">
+        {if let Session::LoggedIn(user) = &self.state.session {
+            html!{<>
+                <span>{&user.name}</span>
+                <button type="button" onclick=self.link.callback(|_| Msg::Logout)>{t("login.logout")}</button>
+            </>}
+        } else {
+            html!{<RouterAnchor<Route> route=Route::LoginPage>{t("login.title")}</RouterAnchor<Route>>}
+        }}
+    </div>
+    <div class="pending">
+        {if self.state.doing > 0 { html!{<span>{tn("layout.saving", self.state.doing, &[])}</span>} } else { html!{} }}
+        {if self.state.failed > 0 {
+            html!{<span class="failed">
+                {tn("layout.failed", self.state.failed, &[])}
+                <button type="button" onclick=self.link.callback(|_| Msg::RetryFailed)>{t("layout.retry")}</button>
+            </span>}
+        } else {
+            html!{}
+        }}
+    </div>
+</header>
+<nav class="hnav"><ul>
+    {self.nav_item(Route::TaggingPage, t("nav.tagging"))}
+    {self.nav_item(Route::NewTagPage, t("nav.new_tag"))}
+    {self.nav_item(Route::StatsPage, t("nav.stats"))}
+    {self.nav_item(Route::SkippedPage, t("nav.skipped"))}
+    {self.nav_item(Route::HiddenPage, t("nav.hidden"))}
+    {self.nav_item(Route::ExportPage, t("nav.export"))}
+</ul>
+<select class="locale" title=t("layout.language") onchange=self.link.callback(Msg::UILocaleState)>
+    {Locale::ALL.iter().map(|locale| html!{
+        <option value=locale.code() selected={*locale == i18n::locale()}>{locale.name()}</option>
+    }).collect::<Html>()}
+</select>
+</nav>
+<ul class="notifications">
+    {self.state.notifications.iter().map(|(id, notification, _)| {
+        let id = *id;
+        let (class, text) = match notification {
+            Notification::Info(text) => ("info", text),
+            Notification::Error(text) => ("error", text)
+        };
+        html!{<li class=class onclick=self.link.callback(move |_| Msg::Dismiss(id))>{text}</li>}
+    }).collect::<Html>()}
+</ul>
+{self.props.children.clone()}
+<footer>{"Magicloud"}</footer>
+    </>}}
+}
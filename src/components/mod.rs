@@ -1,7 +1,23 @@
 mod photo_list;
 mod tagging;
 mod photo;
+mod name_avatar;
+mod bar_chart;
+mod session_stats;
+mod layout;
+mod redirect;
+mod image_viewer;
+mod exif_panel;
+mod folder_tree;
 
 pub use photo_list::*;
 pub use tagging::*;
 pub use photo::*;
+pub use name_avatar::*;
+pub use bar_chart::*;
+pub use session_stats::*;
+pub use layout::*;
+pub use redirect::*;
+pub use image_viewer::*;
+pub use exif_panel::*;
+pub use folder_tree::*;
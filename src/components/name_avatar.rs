@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use yew::prelude::*;
+use yew::services::fetch::*;
+use yew::format::*;
+use anyhow::Error;
+use crate::apis;
+use crate::route::encode_path;
+
+/// Fetches the representative photo of every name that has one, for the pages listing names.
+pub fn fetch_avatars(callback: Callback<HashMap<String, String>>) -> Option<FetchTask> {
+    let req = apis::get("/apis/name_avatars").body(Nothing).unwrap();
+    let on_done = Callback::from(move |response: Response<Json<Result<HashMap<String, String>, Error>>>| {
+        let Json(data) = response.into_body();
+        // Names without avatars are still usable, so a failure here is not fatal.
+        callback.emit(data.unwrap_or_default());
+    });
+    apis::fetch(req, on_done).ok()
+}
+
+/// A name with its representative photo, used wherever names are listed.
+pub struct NameAvatar {
+    props: Props
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub name: String,
+    #[prop_or_default]
+    pub photo: Option<String>
+}
+
+impl Component for NameAvatar {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self { Self { props } }
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender { false }
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+    fn view(&self) -> Html {
+        html! {
+            <span class="avatar">
+                {if let Some(photo) = &self.props.photo {
+                    html! {<img src={format!("/pics/{}", encode_path(photo))} loading="lazy" />}
+                } else {
+                    html! {<span class="placeholder">{self.props.name.chars().next().map(|c| c.to_string()).unwrap_or_default()}</span>}
+                }}
+                <span class="name">{&self.props.name}</span>
+            </span>
+        }
+    }
+}
@@ -1,52 +1,61 @@
 use yew::prelude::*;
-use yew::services::fetch::*;
-use yew::format::*;
-use yew::agent::*;
-use anyhow::Error;
-use crate::event_buses::*;
+use crate::i18n::{t, tf};
+use crate::phash::Hashes;
+use crate::rotation::*;
+use crate::route::encode_path;
+use crate::save_queue::*;
+
+/// The width of the thumbnails in CSS pixels, also asked for at these multiples for denser screens.
+const THUMBNAIL_SIZE: u32 = 160;
+const THUMBNAIL_DENSITIES: [u32; 3] = [1, 2, 3];
 
 pub struct Photo {
     state: State,
-    task: Option<FetchTask>,
     props: Props,
     link: ComponentLink<Self>,
-    event_bus_out: Dispatcher<ListToDetailEventBus>,
-    event_bus_in: Box<dyn Bridge<DetailToListEventBus>>,
-    event_bus_list: Dispatcher<ItemToListEventBus>
-}
-
-enum UpdateStatus {
-    NotYet,
-    Doing,
-    Failed,
-    Succeeded
-}
-impl ToString for UpdateStatus {
-    fn to_string(&self) -> String {
-        match self {
-            UpdateStatus::NotYet => {"notyet"}
-            UpdateStatus::Doing => {"doing"}
-            UpdateStatus::Failed => {"failed"}
-            UpdateStatus::Succeeded => {"succeeded"}
-        }.to_string()
-    }
+    node_ref: NodeRef,
+    _save_queue: Box<dyn Bridge<SaveQueue>>,
+    rotation: Box<dyn Bridge<RotationAgent>>
 }
 
 struct State {
-    tag: Option<String>,
-    update_status: UpdateStatus
+    update_status: UpdateStatus,
+    /// Clockwise degrees the photo was turned by hand.
+    rotation: u32,
+    /// Whether the server has no thumbnail of the photo, so the photo itself is shown.
+    no_thumbnail: bool,
+    scroll_into_view: bool
 }
 
 pub enum Msg {
     ToTag,
-    Done(Option<String>),
-    UpdatedOk,
-    UpdatedErr(Error)
+    SaveQueue(SaveQueueOutput),
+    Rotation((String, u32)),
+    ThumbnailErr,
+    Report,
+    Loaded
 }
 
 #[derive(Clone, Properties)]
 pub struct Props {
     pub photo: String,
+    #[prop_or_default]
+    pub selected: bool,
+    /// Who else is tagging the photo, which cannot be picked meanwhile.
+    #[prop_or_default]
+    pub locked_by: Option<String>,
+    /// Whether neither the thumbnail nor the photo could be loaded.
+    #[prop_or_default]
+    pub broken: bool,
+    pub onclick: Callback<String>,
+    #[prop_or_default]
+    pub onbroken: Callback<String>,
+    /// Reports the photo as broken, offered on the placeholder only if set.
+    #[prop_or_default]
+    pub onreport: Option<Callback<String>>,
+    /// Given the perceptual hashes of the thumbnail once it has loaded, if set.
+    #[prop_or_default]
+    pub onhash: Option<Callback<(String, Hashes)>>
 }
 
 impl Component for Photo {
@@ -54,56 +63,113 @@ impl Component for Photo {
     type Properties = Props;
 
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut rotation = RotationAgent::bridge(link.callback(Msg::Rotation));
+        rotation.send(RotationRequest::Watch(props.photo.clone()));
         Self {
             state: State {
-                tag: None,
-                update_status: UpdateStatus::NotYet
+                update_status: UpdateStatus::NotYet,
+                rotation: 0,
+                no_thumbnail: false,
+                scroll_into_view: props.selected
             },
-            task: None,
             props,
-            event_bus_in: DetailToListEventBus::bridge(link.callback(Msg::Done)),
+            _save_queue: SaveQueue::bridge(link.callback(Msg::SaveQueue)),
+            rotation,
             link,
-            event_bus_out: ListToDetailEventBus::dispatcher(),
-            event_bus_list: ItemToListEventBus::dispatcher()
+            node_ref: NodeRef::default()
         }
     }
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::ToTag => {
-                self.event_bus_out.send(ListToDetailEvents::ToTag(self.props.photo.clone()));
-                true
+                if self.props.locked_by.is_none() && !self.props.broken {
+                    self.props.onclick.emit(self.props.photo.clone());
+                }
+                false
             }
-            Msg::Done(None) => {
-                self.event_bus_list.send(ItemToListEvents::Next);
+            Msg::SaveQueue(SaveQueueOutput::Status(filename, status)) if filename == self.props.photo => {
+                self.state.update_status = status;
                 true
             }
-            Msg::Done(Some(tag)) => {
-                self.state.update_status = UpdateStatus::Doing;
-                let req = Request::post(format!("/apis/name_image?photo_filename={}&name={}", self.props.photo, tag)).body(Nothing).unwrap();
-                let on_done = self.link.callback(move |response: Response<Result<String, Error>>| {
-                    if response.status().is_success() {
-                        Msg::UpdatedOk
-                    } else {
-                        Msg::UpdatedErr(response.into_body().err().unwrap())
-                    }
-                });
-                let task = FetchService::fetch(req, on_done).unwrap();
-                self.task = Some(task);
-                self.event_bus_list.send(ItemToListEvents::Next);
+            Msg::SaveQueue(_) => { false }
+            Msg::Rotation((filename, degrees)) if filename == self.props.photo => {
+                self.state.rotation = degrees;
                 true
             }
-            Msg::UpdatedOk => {
-                self.state.update_status = UpdateStatus::Succeeded;
+            Msg::Rotation(_) => { false }
+            Msg::ThumbnailErr => {
+                if self.state.no_thumbnail {
+                    self.props.onbroken.emit(self.props.photo.clone());
+                    return false;
+                }
+                self.state.no_thumbnail = true;
                 true
             }
-            Msg::UpdatedErr(_e) => {
-                self.state.update_status = UpdateStatus::Failed;
-                true
+            Msg::Loaded => {
+                if let Some(onhash) = &self.props.onhash {
+                    if let Some(hashes) = self.node_ref.cast::<web_sys::HtmlImageElement>().and_then(|img| Hashes::of_image(&img)) {
+                        onhash.emit((self.props.photo.clone(), hashes));
+                    }
+                }
+                false
+            }
+            Msg::Report => {
+                if let Some(onreport) = &self.props.onreport {
+                    onreport.emit(self.props.photo.clone());
+                }
+                false
+            }
+        }
+    }
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        if props.photo != self.props.photo {
+            self.state.no_thumbnail = false;
+            self.rotation.send(RotationRequest::Watch(props.photo.clone()));
+        }
+        self.state.scroll_into_view = props.selected && !self.props.selected;
+        self.props = props;
+        true
+    }
+    fn rendered(&mut self, _first_render: bool) {
+        if self.state.scroll_into_view {
+            self.state.scroll_into_view = false;
+            if let Some(img) = self.node_ref.cast::<web_sys::Element>() {
+                img.scroll_into_view();
             }
         }
     }
-    fn change(&mut self, _: Self::Properties) -> ShouldRender { true }
     fn view(&self) -> Html {
-        html! {<img class={self.state.update_status.to_string()} src={format!("/pics/{}", self.props.photo)} loading="lazy" onclick=self.link.callback(move |_| Msg::ToTag) />}
+        if self.props.broken {
+            return html! {<span ref=self.node_ref.clone() class=classes!("broken", self.props.selected.then_some("selected")) title=self.props.photo.clone()>
+                <span class="placeholder">{t("photo.broken")}</span>
+                {if self.props.onreport.is_some() {
+                    html! {<button type="button" onclick=self.link.callback(|_| Msg::Report)>{t("photo.report")}</button>}
+                } else {
+                    html! {}
+                }}
+            </span>};
+        }
+        let classes = classes!(self.state.update_status.to_string(), self.props.selected.then_some("selected"), self.props.locked_by.is_some().then_some("locked"));
+        let title = match (&self.props.locked_by, self.state.update_status) {
+            (Some(name), _) => Some(tf("photo.locked_by", &[("name", name.clone())])),
+            (None, UpdateStatus::Conflict) => Some(t("photo.conflict")),
+            (None, _) => None
+        };
+        // Thumbnails are too many to read the EXIF data of each, so the browser applies the
+        // orientation and only the turns made by hand come on top.
+        let style = format!("image-orientation: from-image; transform: rotate({}deg);", self.state.rotation);
+        // A space would end a URL in the srcset, so the path is encoded in both.
+        let path = encode_path(&self.props.photo);
+        let (src, srcset) = if self.state.no_thumbnail {
+            (format!("/pics/{}", path), String::new())
+        } else {
+            let srcset = THUMBNAIL_DENSITIES.iter()
+                .map(|density| format!("/thumbs/{}/{} {}x", THUMBNAIL_SIZE * density, path, density))
+                .collect::<Vec<_>>()
+                .join(", ");
+            (format!("/thumbs/{}/{}", THUMBNAIL_SIZE, path), srcset)
+        };
+        html! {<img ref=self.node_ref.clone() class=classes title=title.unwrap_or_default() style=style src=src srcset=srcset loading="lazy"
+            onload=self.link.callback(|_| Msg::Loaded) onerror=self.link.callback(|_| Msg::ThumbnailErr) onclick=self.link.callback(move |_| Msg::ToTag) />}
     }
 }
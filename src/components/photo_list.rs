@@ -1,20 +1,79 @@
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::time::Duration;
 use yew::prelude::*;
 use yew::services::fetch::*;
 use yew::format::*;
+use yew::agent::*;
+use yew::services::timeout::*;
 use anyhow::Error;
-use crate::components::Photo;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use crate::apis;
+use crate::components::{FolderTree, Photo};
 use crate::event_buses::*;
+use crate::i18n::{t, tf, tn};
+use crate::query;
+use crate::save_queue::*;
+use crate::lease::*;
+use crate::live::*;
+use crate::phash::{self, Hashes};
+use crate::burst;
+use crate::prefetch::{self, Prefetcher};
 
 pub struct PhotoList {
     state: State,
     props: Props,
     link: ComponentLink<Self>,
-    event_bus_item: Box<dyn Bridge<ItemToListEventBus>>,
-    task: Option<FetchTask>
+    _event_bus_detail: Box<dyn Bridge<DetailToListEventBus>>,
+    event_bus_session: Dispatcher<SessionEventBus>,
+    event_bus_notification: Dispatcher<NotificationEventBus>,
+    save_queue: Box<dyn Bridge<SaveQueue>>,
+    lease: Option<Box<dyn Bridge<LeaseAgent>>>,
+    _live: Box<dyn Bridge<LiveAgent>>,
+    prefetcher: Prefetcher,
+    /// The selection, list and filter the photos loaded ahead were picked for.
+    prefetched_for: Option<(Option<String>, Rc<Vec<String>>, ListFilter)>,
+    task: Option<FetchTask>,
+    taken_task: Option<FetchTask>,
+    report_tasks: HashMap<String, FetchTask>,
+    /// Grouping waits for a pause in thumbnails loading, as it compares every pair of photos.
+    regroup: Option<TimeoutTask>
 }
 
 struct State {
-    photos: Vec<String>,
+    /// Shared with the folder tree, which rebuilds itself only when the list is a new one.
+    photos: Rc<Vec<String>>,
+    leases: Leases,
+    /// Photos that failed to load, which are passed over.
+    broken: HashSet<String>,
+    /// Photos with a job in the save queue, which the session counters count already.
+    queued: HashSet<String>,
+    /// How many photos hidden here left the list. Being counted as skipped already, they still
+    /// count as listed for the session counters.
+    hidden_here: usize,
+    /// Photos whose names clashed with someone else's, waiting for the tagger to choose.
+    conflicted: HashSet<String>,
+    /// Photos picked for acting on several at once.
+    checked: HashSet<String>,
+    /// Perceptual hashes of the thumbnails loaded so far.
+    hashes: HashMap<String, Hashes>,
+    /// Near-duplicates, by the index of their group in `groups`.
+    group_of: HashMap<String, usize>,
+    groups: Vec<Vec<String>>,
+    /// Whether naming a photo names its near-duplicates too, which is left to the tagger to turn on.
+    name_duplicates: bool,
+    /// Photos named along with a near-duplicate, which are passed over.
+    carried: HashSet<String>,
+    /// When the photos were taken, as Unix seconds, for those the server knows of.
+    taken: HashMap<String, u64>,
+    /// Bursts, by the index of their cluster in `clusters`. They show a single photo standing for
+    /// all of them, and naming it names the whole burst, until the tagger expands one to name its
+    /// photos one by one.
+    cluster_of: HashMap<String, usize>,
+    clusters: Vec<Vec<String>>,
+    expanded: HashSet<usize>,
+    /// Photos a decision was made on here, which naming their burst leaves alone.
+    decided: HashSet<String>,
     getting_photo_list: bool,
     get_photo_list_error: Option<Error>
 }
@@ -23,12 +82,321 @@ pub enum Msg {
     GetPhotoList,
     GetPhotoListOk(Vec<String>),
     GetPhotoListErr(Error),
-    Next(())
+    GetTakenOk(HashMap<String, u64>),
+    ToggleCluster(usize),
+    Detail(DetailToListEvents),
+    Broken(String),
+    Report(String),
+    Check(String),
+    ClearChecked,
+    ResolveNext,
+    Hide(Vec<String>),
+    SaveQueue(SaveQueueOutput),
+    Hash((String, Hashes)),
+    Regroup,
+    UINameDuplicatesState,
+    ReportOk(String),
+    ReportErr(String),
+    Leases(Leases),
+    Live(LiveEvent),
+    UIFilterState(FilterField, ChangeData),
+    Folder(String)
+}
+
+pub enum FilterField {
+    Text,
+    Folder,
+    From,
+    To,
+    Sort
+}
+
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum ListSort {
+    /// In the order the API lists the photos.
+    #[default]
+    Listed,
+    Taken,
+    Filename,
+    Folder
+}
+impl ListSort {
+    pub const ALL: [ListSort; 4] = [ListSort::Listed, ListSort::Taken, ListSort::Filename, ListSort::Folder];
+
+    fn code(&self) -> &'static str {
+        match self {
+            ListSort::Listed => "",
+            ListSort::Taken => "taken",
+            ListSort::Filename => "filename",
+            ListSort::Folder => "folder"
+        }
+    }
+    fn from_code(code: &str) -> Self {
+        ListSort::ALL.iter().copied().find(|sort| sort.code() == code).unwrap_or(ListSort::Listed)
+    }
+    fn label(&self) -> String {
+        match self {
+            ListSort::Listed => t("photo_list.sort_listed"),
+            ListSort::Taken => t("photo_list.sort_taken"),
+            ListSort::Filename => t("photo_list.sort_filename"),
+            ListSort::Folder => t("photo_list.sort_folder")
+        }
+    }
+}
+
+/// Which photos the list shows and in what order.
+#[derive(Clone, Default, PartialEq)]
+pub struct ListFilter {
+    /// Part of the filename.
+    pub text: String,
+    /// Only photos under this folder.
+    pub folder: String,
+    /// The range of dates taken, as `YYYY-MM-DD`, both inclusive.
+    pub from: String,
+    pub to: String,
+    pub sort: ListSort
+}
+impl ListFilter {
+    pub fn from_query(query: &HashMap<String, String>) -> Self {
+        let get = |key: &str| query.get(key).cloned().unwrap_or_default();
+        Self {
+            text: get("filter"),
+            folder: get("folder"),
+            from: get("from"),
+            to: get("to"),
+            sort: ListSort::from_code(&get("sort"))
+        }
+    }
+
+    pub fn to_query(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("filter", self.text.clone()),
+            ("folder", self.folder.clone()),
+            ("from", self.from.clone()),
+            ("to", self.to.clone()),
+            ("sort", self.sort.code().to_string())
+        ]
+    }
+
+    /// What the API is asked to narrow down and order the list by, where the server supports
+    /// it. Otherwise `matches` and the sort in the list do the same on the client. Folders are
+    /// always left to the client, as the folder tree counts the photos of every folder.
+    fn api_query(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("taken_from", self.from.clone()),
+            ("taken_to", self.to.clone()),
+            ("sort", self.sort.code().to_string())
+        ]
+    }
+
+    /// Whether the photo is one to show, given when the photos were taken as far as known.
+    /// Photos of unknown date are left out once a range of dates is asked for, unless the
+    /// server knows the date of none, in which case the range is not offered either.
+    fn matches(&self, photo: &str, taken: &HashMap<String, u64>) -> bool {
+        let folder = self.folder.trim_matches('/');
+        let day = taken.get(photo).map(|secs| secs / SECS_PER_DAY);
+        let from = days_since_epoch(&self.from).filter(|_| !taken.is_empty());
+        let to = days_since_epoch(&self.to).filter(|_| !taken.is_empty());
+        (folder.is_empty() || photo.strip_prefix(folder).map_or(false, |rest| rest.starts_with('/')))
+            && basename(photo).to_lowercase().contains(&self.text.to_lowercase())
+            && from.map_or(true, |from| day.map_or(false, |day| day >= from))
+            && to.map_or(true, |to| day.map_or(false, |day| day <= to))
+    }
+}
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// The day of a `YYYY-MM-DD` date, counted from 1970-01-01.
+fn days_since_epoch(date: &str) -> Option<u64> {
+    let mut parts = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    // Counted in years starting on March 1st, which puts the leap day at their end.
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    u64::try_from(era * 146_097 + day_of_era - 719_468).ok()
+}
+
+/// Orders the photos by when they were taken, those of unknown date last.
+fn sort_by_taken(photos: &mut [&String], taken: &HashMap<String, u64>) {
+    photos.sort_by_key(|photo| taken.get(*photo).copied().unwrap_or(u64::MAX));
+}
+
+fn basename(photo: &str) -> &str {
+    photo.rsplit('/').next().unwrap_or(photo)
 }
 
 #[derive(Clone, Properties)]
 pub struct Props {
-    pub onclick: Callback<String>
+    /// Called with the filename and its position in the shown list when a photo is picked.
+    pub onclick: Callback<(String, usize)>,
+    /// The API listing the photos to show.
+    #[prop_or("/apis/unnamed_images".to_string())]
+    pub source: String,
+    #[prop_or_default]
+    pub selected: Option<String>,
+    /// Where the selection was in the list, to continue from there when the selected photo is gone.
+    #[prop_or_default]
+    pub position: Option<usize>,
+    #[prop_or_default]
+    pub filter: ListFilter,
+    #[prop_or_default]
+    pub onfilter: Callback<ListFilter>,
+    /// Leases the photos while they are shown, so that other taggers work on different ones.
+    #[prop_or_default]
+    pub lease: bool
+}
+
+impl PhotoList {
+    /// The shown photos: those leased to this tagger go first and those leased to others last.
+    fn filtered(&self) -> Vec<&String> {
+        let mut photos = self.state.photos.iter().filter(|photo| self.props.filter.matches(photo, &self.state.taken)).collect::<Vec<_>>();
+        match self.props.filter.sort {
+            ListSort::Filename => photos.sort_by_key(|photo| basename(photo).to_lowercase()),
+            ListSort::Folder => photos.sort(),
+            ListSort::Taken => sort_by_taken(&mut photos, &self.state.taken),
+            ListSort::Listed => {}
+        }
+        photos.sort_by_key(|photo| match (self.state.leases.mine.contains(*photo), self.state.leases.others.contains_key(*photo)) {
+            (true, _) => 0,
+            (false, false) => 1,
+            (false, true) => 2
+        });
+        // A collapsed burst shows its first photo still to be done.
+        let done = |photo: &String| self.state.decided.contains(photo) || self.state.carried.contains(photo);
+        let mut heads: HashMap<usize, &String> = HashMap::new();
+        for photo in &photos {
+            if let Some(cluster) = self.state.cluster_of.get(*photo).filter(|cluster| !self.state.expanded.contains(cluster)) {
+                let head = heads.entry(*cluster).or_insert(photo);
+                if done(head) && !done(photo) {
+                    *head = photo;
+                }
+            }
+        }
+        let hidden = |photo: &String| self.state.cluster_of.get(photo).and_then(|cluster| heads.get(cluster)).map_or(false, |head| *head != photo);
+        // Near-duplicates and the rest of an expanded burst go right after the first of them.
+        let shown = photos.iter().copied().filter(|photo| !hidden(photo)).collect::<HashSet<_>>();
+        let mut placed = HashSet::new();
+        let mut grouped = Vec::with_capacity(photos.len());
+        for photo in photos {
+            if hidden(photo) || !placed.insert(photo) {
+                continue;
+            }
+            grouped.push(photo);
+            let cluster = self.state.cluster_of.get(photo).filter(|cluster| self.state.expanded.contains(cluster));
+            let others = self.duplicates(photo).iter().chain(cluster.map_or(&[][..], |cluster| &self.state.clusters[*cluster]));
+            for other in others.filter(|other| shown.contains(other)) {
+                if placed.insert(other) {
+                    grouped.push(other);
+                }
+            }
+        }
+        grouped
+    }
+
+    /// The burst the photo is in, and whether it is collapsed.
+    fn cluster(&self, photo: &str) -> Option<(usize, bool)> {
+        self.state.cluster_of.get(photo).map(|cluster| (*cluster, !self.state.expanded.contains(cluster)))
+    }
+
+    fn recluster(&mut self) {
+        self.state.clusters = burst::clusters(&self.state.photos, &self.state.taken);
+        self.state.cluster_of = self.state.clusters.iter().enumerate()
+            .flat_map(|(i, cluster)| cluster.iter().map(move |photo| (photo.clone(), i)))
+            .collect();
+        self.state.expanded.clear();
+    }
+
+    /// Names the near-duplicates of the photo, if asked to, and the rest of its burst when
+    /// collapsed, along with it. Only photos the tagger can see are named: those shown in the
+    /// list, and those of the collapsed burst the filter would show. Photos already decided on
+    /// one by one keep their decision.
+    fn name_along(&mut self, filename: &str, name: &str) {
+        let mut visible = self.filtered().into_iter().cloned().collect::<HashSet<_>>();
+        let mut others = if self.state.name_duplicates { self.duplicates(filename).to_vec() } else { vec![] };
+        if let Some((cluster, true)) = self.cluster(filename) {
+            let members = self.state.clusters[cluster].iter()
+                .filter(|member| self.state.photos.contains(*member) && self.props.filter.matches(member, &self.state.taken))
+                .cloned()
+                .collect::<Vec<_>>();
+            visible.extend(members.iter().cloned());
+            others.extend(members);
+        }
+        let mut seen = HashSet::new();
+        others.retain(|other| other != filename && seen.insert(other.clone()) && visible.contains(other)
+            && !self.state.carried.contains(other) && !self.state.decided.contains(other) && !self.state.queued.contains(other) && !self.locked(other));
+        if !others.is_empty() {
+            self.event_bus_notification.send(Notification::Info(tn("photo_list.named_along", others.len(), &[("name", name.to_string())])));
+        }
+        for other in others {
+            if let Some(lease) = self.lease.as_mut() {
+                lease.send(LeaseRequest::Done(other.clone()));
+            }
+            self.save_queue.send(SaveQueueRequest::Enqueue(other.clone(), Decision::Name(name.to_string())));
+            self.state.carried.insert(other);
+        }
+    }
+
+    fn schedule_regroup(&mut self) {
+        if self.regroup.is_none() {
+            self.regroup = Some(TimeoutService::spawn(Duration::from_millis(500), self.link.callback(|_| Msg::Regroup)));
+        }
+    }
+
+    fn duplicates(&self, photo: &str) -> &[String] {
+        self.state.group_of.get(photo).map_or(&[], |group| &self.state.groups[*group])
+    }
+
+    fn source(&self) -> String {
+        let params = query::build(&self.props.filter.api_query());
+        if self.props.source.contains('?') {
+            format!("{}{}", self.props.source, params.replacen('?', "&", 1))
+        } else {
+            format!("{}{}", self.props.source, params)
+        }
+    }
+
+    fn locked(&self, photo: &str) -> bool {
+        self.state.leases.others.contains_key(photo)
+    }
+
+    /// Whether the photo can be moved on to, as neither someone else has it nor it is broken.
+    fn pickable(&self, photo: &str) -> bool {
+        !self.locked(photo) && !self.state.broken.contains(photo) && !self.state.carried.contains(photo)
+    }
+
+    fn position_of(&self, filename: &str) -> Option<usize> {
+        self.filtered().iter().position(|photo| *photo == filename)
+    }
+
+    /// Moves on from the photo, which is finished with one way or another, from where it was
+    /// in the list before, as finishing it may have changed what a collapsed burst shows.
+    fn select_after(&mut self, filename: &str, position: Option<usize>) {
+        if let Some(lease) = self.lease.as_mut() {
+            lease.send(LeaseRequest::Done(filename.to_string()));
+        }
+        let photos = self.filtered();
+        match position {
+            Some(i) => {
+                if let Some((j, photo)) = photos.iter().enumerate().skip(i).find(|(_, photo)| **photo != filename && self.pickable(photo)) {
+                    self.props.onclick.emit(((*photo).clone(), j));
+                }
+            }
+            None => self.select_at(self.props.position.unwrap_or(0))
+        }
+    }
+
+    fn select_at(&self, position: usize) {
+        let photos = self.filtered();
+        if let Some(photo) = photos.get(position).or_else(|| photos.last()) {
+            self.props.onclick.emit(((*photo).clone(), position.min(photos.len() - 1)));
+        }
+    }
 }
 
 impl Component for PhotoList {
@@ -37,23 +405,54 @@ impl Component for PhotoList {
 
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
         link.send_message(Msg::GetPhotoList);
+        let lease = props.lease.then(|| {
+            let mut lease = LeaseAgent::bridge(link.callback(Msg::Leases));
+            lease.send(LeaseRequest::Claim);
+            lease
+        });
         Self {
             state: State {
-                photos: vec![],
+                photos: Rc::new(vec![]),
+                leases: Leases::default(),
+                broken: HashSet::new(),
+                queued: HashSet::new(),
+                conflicted: HashSet::new(),
+                hidden_here: 0,
+                checked: HashSet::new(),
+                hashes: HashMap::new(),
+                group_of: HashMap::new(),
+                groups: vec![],
+                name_duplicates: false,
+                carried: HashSet::new(),
+                taken: HashMap::new(),
+                cluster_of: HashMap::new(),
+                clusters: vec![],
+                expanded: HashSet::new(),
+                decided: HashSet::new(),
                 getting_photo_list: false,
                 get_photo_list_error: None
             },
             props,
-            event_bus_item: ItemToListEventBus::bridge(link.callback(Msg::Next)),
+            _event_bus_detail: DetailToListEventBus::bridge(link.callback(Msg::Detail)),
+            event_bus_session: SessionEventBus::dispatcher(),
+            event_bus_notification: NotificationEventBus::dispatcher(),
+            save_queue: SaveQueue::bridge(link.callback(Msg::SaveQueue)),
+            lease,
+            _live: LiveAgent::bridge(link.callback(Msg::Live)),
+            prefetcher: Prefetcher::new(),
+            prefetched_for: None,
             link,
-            task: None
+            task: None,
+            taken_task: None,
+            report_tasks: HashMap::new(),
+            regroup: None
         }
     }
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::GetPhotoList => {
                 self.state.getting_photo_list = true;
-                let req = Request::get("/apis/unnamed_images").body(Nothing).unwrap();
+                let req = apis::get(self.source()).body(Nothing).unwrap();
                 let on_done = self.link.callback(move |response: Response<Json<Result<Vec<String>, Error>>>| {
                     let Json(data) = response.into_body();
                     match data {
@@ -61,8 +460,16 @@ impl Component for PhotoList {
                         Err(e) => Msg::GetPhotoListErr(e)
                     }
                 });
-                let task = FetchService::fetch(req, on_done).unwrap();
+                let task = apis::fetch(req, on_done).unwrap();
                 self.task = Some(task);
+                // Without times from the server there are no bursts, as file numbers alone say nothing of
+                // how far apart photos were taken.
+                let req = apis::get("/apis/taken_times").body(Nothing).unwrap();
+                let on_done = self.link.callback(|response: Response<Json<Result<HashMap<String, u64>, Error>>>| {
+                    let Json(data) = response.into_body();
+                    Msg::GetTakenOk(data.unwrap_or_default())
+                });
+                self.taken_task = apis::fetch(req, on_done).ok();
                 true
             }
             Msg::GetPhotoListErr(e) => {
@@ -72,35 +479,365 @@ impl Component for PhotoList {
             }
             Msg::GetPhotoListOk(strs) => {
                 self.state.getting_photo_list = false;
-                self.state.photos = strs;
+                self.event_bus_session.send(SessionEvents::ListLoaded(strs.len()));
+                self.state.photos = Rc::new(strs);
+                self.state.hidden_here = 0;
+                self.schedule_regroup();
+                self.recluster();
+                let selected_listed = self.props.selected.as_ref().map_or(false, |s| self.state.photos.contains(s));
+                if let (false, Some(position)) = (selected_listed, self.props.position) {
+                    self.select_at(position);
+                }
+                true
+            }
+            Msg::GetTakenOk(taken) => {
+                self.taken_task = None;
+                self.state.taken = taken;
+                self.recluster();
+                true
+            }
+            Msg::ToggleCluster(cluster) => {
+                if !self.state.expanded.remove(&cluster) {
+                    self.state.expanded.insert(cluster);
+                }
+                true
+            }
+            Msg::Detail(DetailToListEvents::Done(filename, decision)) => {
+                let position = self.position_of(&filename);
+                self.state.decided.insert(filename.clone());
+                if let Decision::Name(name) = decision {
+                    self.name_along(&filename, &name);
+                }
+                self.select_after(&filename, position);
+                true
+            }
+            Msg::Hash((photo, hashes)) => {
+                self.state.hashes.insert(photo, hashes);
+                self.schedule_regroup();
+                false
+            }
+            Msg::Regroup => {
+                self.regroup = None;
+                // Photos gone from the list are no one's duplicates any more.
+                let listed = self.state.photos.iter().collect::<HashSet<_>>();
+                self.state.hashes.retain(|photo, _| listed.contains(photo));
+                self.state.groups = phash::groups(self.state.hashes.iter());
+                self.state.group_of = self.state.groups.iter().enumerate()
+                    .flat_map(|(i, group)| group.iter().map(move |photo| (photo.clone(), i)))
+                    .collect();
+                true
+            }
+            Msg::UINameDuplicatesState => {
+                self.state.name_duplicates = !self.state.name_duplicates;
+                true
+            }
+            Msg::Detail(DetailToListEvents::Broken(filename)) => {
+                let position = self.position_of(&filename);
+                self.state.broken.insert(filename.clone());
+                self.event_bus_notification.send(Notification::Info(tf("photo_list.broken_skipped", &[("photo", filename.clone())])));
+                self.select_after(&filename, position);
+                true
+            }
+            Msg::Broken(filename) => {
+                self.state.broken.insert(filename)
+            }
+            Msg::Report(filename) => {
+                let req = apis::post(format!("/apis/report_broken?photo_filename={}", utf8_percent_encode(&filename, NON_ALPHANUMERIC))).body(Nothing).unwrap();
+                let photo = filename.clone();
+                let on_done = self.link.callback(move |response: Response<Text>| {
+                    if response.status().is_success() { Msg::ReportOk(photo.clone()) } else { Msg::ReportErr(photo.clone()) }
+                });
+                match apis::fetch(req, on_done) {
+                    Ok(task) => { self.report_tasks.insert(filename, task); }
+                    Err(_) => self.link.send_message(Msg::ReportErr(filename))
+                }
+                false
+            }
+            Msg::Check(filename) => {
+                if !self.state.checked.remove(&filename) {
+                    self.state.checked.insert(filename);
+                }
+                true
+            }
+            Msg::ClearChecked => {
+                self.state.checked.clear();
+                true
+            }
+            Msg::Hide(photos) => {
+                if let Some(selected) = self.props.selected.clone().filter(|selected| photos.contains(selected)) {
+                    let position = self.position_of(&selected);
+                    self.select_after(&selected, position);
+                }
+                for photo in photos {
+                    self.state.checked.remove(&photo);
+                    self.save_queue.send(SaveQueueRequest::Enqueue(photo, Decision::Hide));
+                }
+                true
+            }
+            Msg::SaveQueue(SaveQueueOutput::Status(filename, UpdateStatus::Hidden)) => {
+                self.state.queued.insert(filename.clone());
+                // Hidden photos leave the list, though not from under the tagger still looking at one.
+                if self.props.selected.as_ref() == Some(&filename) {
+                    return false;
+                }
+                if self.state.photos.contains(&filename) {
+                    Rc::make_mut(&mut self.state.photos).retain(|photo| *photo != filename);
+                    self.state.hidden_here += 1;
+                    self.schedule_regroup();
+                }
                 true
             }
-            Msg::Next(_) => {
-                yew::utils::document().get_element_by_id("nav").unwrap().scroll_by_with_x_and_y(0.0, 300.0);
+            Msg::SaveQueue(SaveQueueOutput::Status(filename, status)) => {
+                self.state.queued.insert(filename.clone());
+                if status == UpdateStatus::Conflict {
+                    self.state.conflicted.insert(filename)
+                } else {
+                    self.state.conflicted.remove(&filename)
+                }
+            }
+            Msg::ResolveNext => {
+                let photos = self.filtered();
+                if let Some((i, photo)) = photos.iter().enumerate().find(|(_, photo)| self.state.conflicted.contains(**photo)) {
+                    self.props.onclick.emit(((*photo).clone(), i));
+                }
+                false
+            }
+            Msg::SaveQueue(_) => { false }
+            Msg::ReportOk(filename) => {
+                self.report_tasks.remove(&filename);
+                self.event_bus_notification.send(Notification::Info(tf("photo_list.reported", &[("photo", filename)])));
+                false
+            }
+            Msg::ReportErr(filename) => {
+                self.report_tasks.remove(&filename);
+                self.event_bus_notification.send(Notification::Error(tf("photo_list.report_failed", &[("photo", filename)])));
+                false
+            }
+            Msg::Live(LiveEvent::ImageNamed { photo_filename, .. }) => {
+                // The selected photo stays, so that it does not vanish from under the tagger. So do
+                // the photos named here, which the session counters count as saved already.
+                if self.props.selected.as_ref() == Some(&photo_filename) || self.state.queued.contains(&photo_filename) {
+                    return false;
+                }
+                if !self.state.photos.contains(&photo_filename) {
+                    return false;
+                }
+                Rc::make_mut(&mut self.state.photos).retain(|photo| *photo != photo_filename);
+                self.event_bus_session.send(SessionEvents::ListLoaded(self.state.photos.len() + self.state.hidden_here));
+                self.schedule_regroup();
+                true
+            }
+            Msg::Live(_) => { false }
+            Msg::Leases(leases) => {
+                self.state.leases = leases;
                 true
             }
+            Msg::UIFilterState(field, v) => {
+                let v = match v {
+                    ChangeData::Value(v) => v,
+                    ChangeData::Select(select) => select.value(),
+                    ChangeData::Files(_) => return false
+                };
+                let mut filter = self.props.filter.clone();
+                match field {
+                    FilterField::Text => filter.text = v,
+                    FilterField::Folder => filter.folder = v,
+                    FilterField::From => filter.from = v,
+                    FilterField::To => filter.to = v,
+                    FilterField::Sort => filter.sort = ListSort::from_code(&v)
+                }
+                self.props.onfilter.emit(filter);
+                false
+            }
+            Msg::Folder(folder) => {
+                self.props.onfilter.emit(ListFilter { folder, ..self.props.filter.clone() });
+                false
+            }
+        }
+    }
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        let source = self.source();
+        self.props = props;
+        if self.source() != source {
+            self.link.send_message(Msg::GetPhotoList);
+        }
+        true
+    }
+    fn rendered(&mut self, _first_render: bool) {
+        // The photos coming up next follow the selection and the list, but not every render.
+        let unchanged = self.prefetched_for.as_ref().map_or(false, |(selected, photos, filter)| {
+            *selected == self.props.selected && Rc::ptr_eq(photos, &self.state.photos) && *filter == self.props.filter
+        });
+        if unchanged {
+            return;
+        }
+        self.prefetched_for = Some((self.props.selected.clone(), self.state.photos.clone(), self.props.filter.clone()));
+        let photos = self.filtered();
+        let next = match self.props.selected.as_ref().and_then(|selected| photos.iter().position(|photo| *photo == selected)) {
+            Some(i) => photos.iter().skip(i + 1).filter(|photo| self.pickable(photo)).take(prefetch::WINDOW).map(|photo| (*photo).clone()).collect::<Vec<_>>(),
+            None => vec![]
+        };
+        self.prefetcher.prefetch(&next);
+    }
+    fn destroy(&mut self) {
+        if let Some(lease) = self.lease.as_mut() {
+            lease.send(LeaseRequest::Release);
         }
     }
-    fn change(&mut self, _: Self::Properties) -> ShouldRender { true }
     fn view(&self) -> Html {
         if self.state.getting_photo_list {
-            html! {<div>{"Loading..."}</div>}
+            html! {<div>{t("photo_list.loading")}</div>}
         } else {
             if let Some(e) = &self.state.get_photo_list_error {
                 html! {<div>{format!("{}", e)}</div>}
             } else {
-                html!{
-                    <div id="nav" class="nav"><ul>{self.state.photos.iter().map(|filename| html! {<li><Photo photo={filename.clone()}/></li>}).collect::<Html>()}</ul></div>
-                    // <ul>{self.state.photos.iter().map(|y| {
-                    //     let x = y.clone();
-                    //     let z = y.clone();
-                    //     let onclick = {
-                    //         self.link.callback(move |()| Msg::ToTag(x.clone()));
-                    //         self.props.onclick.reform(move |_| z.clone())
-                    //     };
-                    //     html! {<li><img id={y.to_string()} src={format!("/pics/{}", y)} loading="lazy" onclick=onclick /></li>}
-                    // }).collect::<Html>()}</ul>
-                }
-            }
-        }    }
+                let onclick = self.props.onclick.clone();
+                // Dates are only offered when the server knows when the photos were taken.
+                let dated = !self.state.taken.is_empty();
+                html!{<>
+                    <FolderTree photos=self.state.photos.clone() selected=self.props.filter.folder.clone() onselect=self.link.callback(Msg::Folder) />
+                    <div class="filters">
+                        <label><input type="checkbox" checked=self.state.name_duplicates onclick=self.link.callback(|_| Msg::UINameDuplicatesState) />{t("photo_list.name_duplicates")}</label>
+                        <input type="search" placeholder=t("photo_list.filter") value={self.props.filter.text.clone()} onchange=self.link.callback(|v| Msg::UIFilterState(FilterField::Text, v)) />
+                        <input type="search" placeholder=t("photo_list.folder") value={self.props.filter.folder.clone()} onchange=self.link.callback(|v| Msg::UIFilterState(FilterField::Folder, v)) />
+                        {if dated {
+                            html! {<>
+                                <label>{t("photo_list.taken_from")}<input type="date" value={self.props.filter.from.clone()} onchange=self.link.callback(|v| Msg::UIFilterState(FilterField::From, v)) /></label>
+                                <label>{t("photo_list.taken_to")}<input type="date" value={self.props.filter.to.clone()} onchange=self.link.callback(|v| Msg::UIFilterState(FilterField::To, v)) /></label>
+                            </>}
+                        } else {
+                            html! {}
+                        }}
+                        <select onchange=self.link.callback(|v| Msg::UIFilterState(FilterField::Sort, v))>
+                            {ListSort::ALL.iter().filter(|sort| dated || **sort != ListSort::Taken).map(|sort| html! {
+                                <option value=sort.code() selected={*sort == self.props.filter.sort}>{sort.label()}</option>
+                            }).collect::<Html>()}
+                        </select>
+                    </div>
+                    {if self.state.checked.is_empty() {
+                        html! {}
+                    } else {
+                        let checked = self.state.checked.iter().cloned().collect::<Vec<_>>();
+                        html! {<div class="batch">
+                            <span>{tn("photo_list.checked", checked.len(), &[])}</span>
+                            <button type="button" onclick=self.link.callback(move |_| Msg::Hide(checked.clone()))>{t("photo_list.hide_checked")}</button>
+                            <button type="button" onclick=self.link.callback(|_| Msg::ClearChecked)>{t("photo_list.clear_checked")}</button>
+                        </div>}
+                    }}
+                    {if self.state.conflicted.is_empty() {
+                        html! {}
+                    } else {
+                        html! {<div class="conflicts">
+                            <span>{tn("photo_list.conflicts", self.state.conflicted.len(), &[])}</span>
+                            <button type="button" onclick=self.link.callback(|_| Msg::ResolveNext)>{t("photo_list.resolve_next")}</button>
+                        </div>}
+                    }}
+                    <div id="nav" class="nav"><ul>{{
+                    let mut toggled = HashSet::new();
+                    self.filtered().into_iter().enumerate().map(|(i, filename)| {
+                        let selected = self.props.selected.as_ref() == Some(filename);
+                        let locked_by = self.state.leases.others.get(filename).cloned();
+                        let broken = self.state.broken.contains(filename);
+                        let checked = self.state.checked.contains(filename);
+                        let duplicates = self.duplicates(filename).len();
+                        let (check, hide) = (filename.clone(), filename.clone());
+                        // The first photo shown of a burst opens and closes it.
+                        let cluster = self.cluster(filename);
+                        let toggle = cluster.filter(|(cluster, _)| toggled.insert(*cluster));
+                        html! {<li key=filename.clone() class=classes!(checked.then_some("checked"), (duplicates > 0).then_some("duplicate"), cluster.map(|_| "burst"))>
+                            {match toggle {
+                                Some((cluster, collapsed)) => {
+                                    let size = self.state.clusters[cluster].len();
+                                    let (label, title) = if collapsed {
+                                        (format!("▸ +{}", size - 1), tn("photo_list.expand_burst", size, &[]))
+                                    } else {
+                                        ("▾".to_string(), tn("photo_list.collapse_burst", size, &[]))
+                                    };
+                                    html! {<button type="button" class="burst" title=title onclick=self.link.callback(move |_| Msg::ToggleCluster(cluster))>{label}</button>}
+                                }
+                                None => html! {}
+                            }}
+                            {if duplicates > 0 {
+                                html! {<span class="group" title=tn("photo_list.duplicates", duplicates - 1, &[])>{format!("×{}", duplicates)}</span>}
+                            } else {
+                                html! {}
+                            }}
+                            <input type="checkbox" checked=checked onclick=self.link.callback(move |_| Msg::Check(check.clone())) />
+                            <Photo photo={filename.clone()} selected=selected locked_by=locked_by broken=broken
+                                onclick=onclick.reform(move |photo| (photo, i)) onbroken=self.link.callback(Msg::Broken) onreport=Some(self.link.callback(Msg::Report)) onhash=Some(self.link.callback(Msg::Hash)) />
+                            <button type="button" class="hide" title=t("photo_list.hide") onclick=self.link.callback(move |_| Msg::Hide(vec![hide.clone()]))>{"×"}</button>
+                        </li>}
+                    }).collect::<Html>()
+                    }}</ul></div>
+                </>}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dated(from: &str, to: &str) -> ListFilter {
+        ListFilter { from: from.to_string(), to: to.to_string(), ..ListFilter::default() }
+    }
+
+    #[test]
+    fn counts_days_from_the_epoch() {
+        assert_eq!(days_since_epoch("1970-01-01"), Some(0));
+        assert_eq!(days_since_epoch("1970-01-02"), Some(1));
+        assert_eq!(days_since_epoch("2000-01-01"), Some(10_957));
+        assert_eq!(days_since_epoch("1969-12-31"), None);
+        assert_eq!(days_since_epoch(""), None);
+        assert_eq!(days_since_epoch("2020-13-01"), None);
+    }
+
+    #[test]
+    fn counts_leap_days() {
+        assert_eq!(days_since_epoch("2020-03-01").unwrap() - days_since_epoch("2020-02-28").unwrap(), 2);
+        assert_eq!(days_since_epoch("2000-03-01").unwrap() - days_since_epoch("2000-02-28").unwrap(), 2);
+        assert_eq!(days_since_epoch("2100-03-01").unwrap() - days_since_epoch("2100-02-28").unwrap(), 1);
+        assert_eq!(days_since_epoch("2021-01-01").unwrap() - days_since_epoch("2020-01-01").unwrap(), 366);
+    }
+
+    #[test]
+    fn date_range_includes_both_boundary_days() {
+        let day = days_since_epoch("2020-06-15").unwrap() * SECS_PER_DAY;
+        let taken = [
+            ("first.jpg".to_string(), day),
+            ("last.jpg".to_string(), day + SECS_PER_DAY - 1),
+            ("after.jpg".to_string(), day + SECS_PER_DAY),
+            ("before.jpg".to_string(), day - 1)
+        ].into_iter().collect::<HashMap<_, _>>();
+        let filter = dated("2020-06-15", "2020-06-15");
+        assert!(filter.matches("first.jpg", &taken));
+        assert!(filter.matches("last.jpg", &taken));
+        assert!(!filter.matches("after.jpg", &taken));
+        assert!(!filter.matches("before.jpg", &taken));
+        assert!(dated("", "2020-06-15").matches("before.jpg", &taken));
+        assert!(dated("2020-06-16", "").matches("after.jpg", &taken));
+    }
+
+    #[test]
+    fn photos_without_a_date() {
+        let taken = [("dated.jpg".to_string(), 0)].into_iter().collect::<HashMap<_, _>>();
+        assert!(!dated("1970-01-01", "").matches("undated.jpg", &taken));
+        assert!(dated("", "").matches("undated.jpg", &taken));
+        // Without any dates from the server, the range is not applied at all.
+        assert!(dated("1970-01-01", "1970-01-01").matches("undated.jpg", &HashMap::new()));
+
+        let (a, b, undated) = ("a.jpg".to_string(), "b.jpg".to_string(), "undated.jpg".to_string());
+        let taken = [(a.clone(), 20), (b.clone(), 10)].into_iter().collect::<HashMap<_, _>>();
+        let mut photos = vec![&undated, &a, &b];
+        sort_by_taken(&mut photos, &taken);
+        assert_eq!(photos, vec![&b, &a, &undated]);
+    }
+
+    #[test]
+    fn text_matches_the_filename_only() {
+        let filter = ListFilter { text: "summer".to_string(), ..ListFilter::default() };
+        assert!(filter.matches("2020/Summer_01.jpg", &HashMap::new()));
+        assert!(!filter.matches("Summer 2020/IMG_01.jpg", &HashMap::new()));
+    }
 }
@@ -0,0 +1,36 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use yew_router::agent::RouteRequest;
+use crate::route::Route;
+
+/// Replaces the current history entry with `to` as soon as it is rendered.
+pub struct Redirect {
+    props: Props,
+    router: RouteAgentDispatcher
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    pub to: Route
+}
+
+impl Component for Redirect {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self {
+        Self {
+            props,
+            router: RouteAgentDispatcher::new()
+        }
+    }
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender { false }
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+    fn rendered(&mut self, _first_render: bool) {
+        self.router.send(RouteRequest::ReplaceRoute(yew_router::route::Route::from(self.props.to.clone())));
+    }
+    fn view(&self) -> Html { html!{} }
+}
@@ -0,0 +1,86 @@
+use yew::prelude::*;
+use crate::event_buses::*;
+use crate::i18n::{number, tf};
+
+/// Live counters of the current tagging session, fed by the photo status transitions.
+pub struct SessionStats {
+    state: State,
+    _event_bus_in: Box<dyn Bridge<SessionEventBus>>
+}
+
+struct State {
+    /// Milliseconds since epoch when the session started.
+    started_at: f64,
+    listed: usize,
+    saving: usize,
+    saved: usize,
+    failed: usize,
+    skipped: usize
+}
+
+impl State {
+    fn remaining(&self) -> usize {
+        self.listed.saturating_sub(self.saving + self.skipped)
+    }
+
+    /// Estimated seconds left at the pace of this session so far.
+    fn eta(&self) -> Option<f64> {
+        let done = self.saved + self.skipped;
+        let elapsed = (js_sys::Date::now() - self.started_at) / 1000.0;
+        if done == 0 || elapsed <= 0.0 {
+            None
+        } else {
+            Some(self.remaining() as f64 * elapsed / done as f64)
+        }
+    }
+}
+
+fn format_duration(secs: f64) -> String {
+    let minutes = (secs / 60.0).round() as u64;
+    if minutes >= 60 {
+        tf("duration.hours_minutes", &[("hours", number((minutes / 60) as f64, 0)), ("minutes", number((minutes % 60) as f64, 0))])
+    } else {
+        tf("duration.minutes", &[("minutes", number(minutes as f64, 0))])
+    }
+}
+
+impl Component for SessionStats {
+    type Message = SessionEvents;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            state: State {
+                started_at: js_sys::Date::now(),
+                listed: 0,
+                saving: 0,
+                saved: 0,
+                failed: 0,
+                skipped: 0
+            },
+            _event_bus_in: SessionEventBus::bridge(link.callback(|e| e))
+        }
+    }
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            SessionEvents::ListLoaded(n) => { self.state.listed = n }
+            SessionEvents::Saving => { self.state.saving += 1 }
+            SessionEvents::Saved => { self.state.saved += 1 }
+            SessionEvents::SaveFailed => { self.state.failed += 1 }
+            SessionEvents::Skipped => { self.state.skipped += 1 }
+        }
+        true
+    }
+    fn change(&mut self, _: Self::Properties) -> ShouldRender { true }
+    fn view(&self) -> Html {
+        html! {
+            <ul class="session-stats">
+                <li>{tf("session.saved", &[("count", number(self.state.saved as f64, 0))])}</li>
+                <li>{tf("session.skipped", &[("count", number(self.state.skipped as f64, 0))])}</li>
+                <li class={if self.state.failed > 0 { "failed" } else { "" }}>{tf("session.failed", &[("count", number(self.state.failed as f64, 0))])}</li>
+                <li>{tf("session.remaining", &[("count", number(self.state.remaining() as f64, 0))])}</li>
+                <li>{tf("session.eta", &[("eta", self.state.eta().map(format_duration).unwrap_or_else(|| "-".to_string()))])}</li>
+            </ul>
+        }
+    }
+}
@@ -1,32 +1,85 @@
+use std::collections::HashMap;
 use yew::prelude::*;
+use yew::services::fetch::*;
+use yew::format::*;
 use serde_json::from_str;
 use yew::agent::*;
+use anyhow::Error;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use crate::apis;
+use crate::components::{ExifPanel, ImageViewer, NameAvatar};
+use crate::metadata::Metadata;
+use crate::rotation::*;
+use crate::route::encode_path;
 use crate::event_buses::*;
+use crate::i18n::{t, tf};
+use crate::save_queue::*;
+use crate::skip_reason::SkipReason;
+
+const MAX_SUGGESTIONS: usize = 10;
 
 pub struct Tagging {
     state: State,
     props: Props,
     link: ComponentLink<Self>,
-    event_bus_in: Box<dyn Bridge<ListToDetailEventBus>>,
-    event_bus_out: Dispatcher<DetailToListEventBus>
+    event_bus_out: Dispatcher<DetailToListEventBus>,
+    event_bus_notification: Dispatcher<NotificationEventBus>,
+    save_queue: Box<dyn Bridge<SaveQueue>>,
+    rotation: Box<dyn Bridge<RotationAgent>>,
+    task: Option<FetchTask>
 }
 
 struct State {
     photo: Option<String>,
     tags: Vec<String>,
-    value: String
+    avatars: HashMap<String, String>,
+    value: String,
+    show_suggestions: bool,
+    /// Photos someone else named before the tagger's name got saved.
+    conflicts: HashMap<String, Conflict>,
+    /// The EXIF details of the photo, once read.
+    metadata: Option<Metadata>,
+    /// Clockwise degrees the photos were turned by hand.
+    rotations: HashMap<String, u32>
 }
 
 pub enum Msg {
     Save,
-    Cancel,
-    ToTag(String),
-    UITagValueState(ChangeData)
+    Skip(SkipReason),
+    Hide,
+    UITagValueState(InputData),
+    PickSuggestion(String),
+    SetAvatar,
+    SetAvatarOk(String, String),
+    SetAvatarErr,
+    SaveQueue(SaveQueueOutput),
+    Resolve(Resolution),
+    Metadata(Metadata),
+    Rotation((String, u32)),
+    Rotate(u32),
+    Broken
 }
 
 #[derive(Clone, Properties)]
 pub struct Props {
-    pub tags_json: String
+    pub tags_json: String,
+    /// Name to representative photo filename, as a JSON object.
+    pub avatars_json: String,
+    #[prop_or_default]
+    pub photo: Option<String>
+}
+
+impl Tagging {
+    fn suggestions(&self) -> Vec<&String> {
+        let value = self.state.value.to_lowercase();
+        if value.is_empty() {
+            return vec![];
+        }
+        self.state.tags.iter()
+            .filter(|tag| tag.to_lowercase().contains(&value))
+            .take(MAX_SUGGESTIONS)
+            .collect()
+    }
 }
 
 impl Component for Tagging {
@@ -34,53 +87,187 @@ impl Component for Tagging {
     type Properties = Props;
 
     fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
+        let mut rotation = RotationAgent::bridge(link.callback(Msg::Rotation));
+        if let Some(photo) = &props.photo {
+            rotation.send(RotationRequest::Watch(photo.clone()));
+        }
         Self {
             state: State {
-                photo: None,
+                photo: props.photo.clone(),
                 tags: from_str(&props.tags_json).unwrap(),
-                value: "".to_string()
+                avatars: from_str(&props.avatars_json).unwrap(),
+                value: "".to_string(),
+                show_suggestions: false,
+                conflicts: HashMap::new(),
+                metadata: None,
+                rotations: HashMap::new()
             },
             props,
-            event_bus_in: ListToDetailEventBus::bridge(link.callback(Msg::ToTag)),
+            save_queue: SaveQueue::bridge(link.callback(Msg::SaveQueue)),
+            rotation,
             link,
-            event_bus_out: DetailToListEventBus::dispatcher()
+            event_bus_out: DetailToListEventBus::dispatcher(),
+            event_bus_notification: NotificationEventBus::dispatcher(),
+            task: None
         }
     }
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::Save => {
-                self.event_bus_out.send(DetailToListEvents::Done(Some(self.state.value.clone())));
+                if let Some(photo) = &self.state.photo {
+                    let decision = Decision::Name(self.state.value.clone());
+                    self.save_queue.send(SaveQueueRequest::Enqueue(photo.clone(), decision.clone()));
+                    self.event_bus_out.send(DetailToListEvents::Done(photo.clone(), decision));
+                }
                 false
             }
-            Msg::Cancel => {
-                self.event_bus_out.send(DetailToListEvents::Done(None));
+            Msg::Skip(reason) => {
+                if let Some(photo) = &self.state.photo {
+                    self.save_queue.send(SaveQueueRequest::Enqueue(photo.clone(), Decision::Skip(reason)));
+                    self.event_bus_out.send(DetailToListEvents::Done(photo.clone(), Decision::Skip(reason)));
+                }
                 false
             }
-            Msg::ToTag(filename) => {
-                self.state.photo = Some(filename);
-                true
+            Msg::Hide => {
+                if let Some(photo) = &self.state.photo {
+                    self.save_queue.send(SaveQueueRequest::Enqueue(photo.clone(), Decision::Hide));
+                    self.event_bus_out.send(DetailToListEvents::Done(photo.clone(), Decision::Hide));
+                }
+                false
             }
             Msg::UITagValueState(v) => {
-                match v {
-                    ChangeData::Value(v) => { self.state.value = v }
-                    ChangeData::Select(_) => {}
-                    ChangeData::Files(_) => {}
+                self.state.value = v.value;
+                self.state.show_suggestions = true;
+                true
+            }
+            Msg::PickSuggestion(tag) => {
+                self.state.value = tag;
+                self.state.show_suggestions = false;
+                true
+            }
+            Msg::SetAvatar => {
+                if let Some(photo) = &self.state.photo {
+                    let name = self.state.value.clone();
+                    let photo = photo.clone();
+                    let req = apis::post(format!("/apis/name_avatar?name={}&photo_filename={}",
+                        utf8_percent_encode(&name, NON_ALPHANUMERIC),
+                        utf8_percent_encode(&photo, NON_ALPHANUMERIC))).body(Nothing).unwrap();
+                    let on_done = self.link.callback(move |response: Response<Result<String, Error>>| {
+                        if response.status().is_success() {
+                            Msg::SetAvatarOk(name.clone(), photo.clone())
+                        } else {
+                            Msg::SetAvatarErr
+                        }
+                    });
+                    self.task = apis::fetch(req, on_done).ok();
                 }
                 false
             }
+            Msg::SetAvatarOk(name, photo) => {
+                self.event_bus_notification.send(Notification::Info(tf("tagging.avatar_set", &[("name", name.clone())])));
+                self.state.avatars.insert(name, photo);
+                true
+            }
+            Msg::SetAvatarErr => {
+                self.event_bus_notification.send(Notification::Error(t("tagging.avatar_failed")));
+                false
+            }
+            Msg::SaveQueue(SaveQueueOutput::Conflict(photo, conflict)) => {
+                self.state.conflicts.insert(photo, conflict);
+                true
+            }
+            Msg::SaveQueue(SaveQueueOutput::Status(photo, status)) if status != UpdateStatus::Conflict => {
+                self.state.conflicts.remove(&photo).is_some()
+            }
+            Msg::SaveQueue(_) => { false }
+            Msg::Metadata(metadata) => {
+                self.state.metadata = Some(metadata);
+                true
+            }
+            Msg::Rotation((photo, degrees)) => {
+                let shown = self.state.photo.as_ref() == Some(&photo);
+                self.state.rotations.insert(photo, degrees);
+                shown
+            }
+            Msg::Rotate(degrees) => {
+                if let Some(photo) = &self.state.photo {
+                    self.rotation.send(RotationRequest::Rotate(photo.clone(), degrees));
+                }
+                false
+            }
+            Msg::Broken => {
+                if let Some(photo) = &self.state.photo {
+                    self.event_bus_out.send(DetailToListEvents::Broken(photo.clone()));
+                }
+                false
+            }
+            Msg::Resolve(resolution) => {
+                if let Some(photo) = &self.state.photo {
+                    self.save_queue.send(SaveQueueRequest::Resolve(photo.clone(), resolution));
+                }
+                false
+            }
+        }
+    }
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.state.tags = from_str(&props.tags_json).unwrap();
+        self.state.avatars = from_str(&props.avatars_json).unwrap();
+        if self.state.photo != props.photo {
+            self.state.metadata = None;
+            if let Some(photo) = &props.photo {
+                self.rotation.send(RotationRequest::Watch(photo.clone()));
+            }
         }
+        self.state.photo = props.photo.clone();
+        self.props = props;
+        true
     }
-    fn change(&mut self, _: Self::Properties) -> ShouldRender { true }
     fn view(&self) -> Html {
         if let Some(photo) = &self.state.photo {
+            let (upright, mirrored) = self.state.metadata.as_ref().map(Metadata::upright).unwrap_or((0, false));
+            let rotation = (upright + self.state.rotations.get(photo).copied().unwrap_or(0)) % 360;
             html! {<>
-                <img src={format!("/pics/{}", photo)} />
-                <input type="text" list="tags" value={self.state.value.clone()} onchange=self.link.callback( move |v| Msg::UITagValueState(v)) />
-                <datalist id="tags">
-                    {self.state.tags.iter().map(|tag| html! {<option>{tag}</option>}).collect::<Html>()}
-                </datalist>
-                <button type="button" onclick=self.link.callback(move |_| Msg::Save)>{"Save & Next"}</button>
-                <button type="button" onclick=self.link.callback(move |_| Msg::Cancel)>{"Cancel & Next"}</button>
+                <ImageViewer src={format!("/pics/{}", encode_path(photo))} rotation=rotation mirrored=mirrored onerror=self.link.callback(|_| Msg::Broken) />
+                <span class="rotate">
+                    <button type="button" title=t("tagging.rotate_left") onclick=self.link.callback(|_| Msg::Rotate(270))>{"⟲"}</button>
+                    <button type="button" title=t("tagging.rotate_right") onclick=self.link.callback(|_| Msg::Rotate(90))>{"⟳"}</button>
+                </span>
+                <ExifPanel photo=photo.clone() onmetadata=self.link.callback(Msg::Metadata) />
+                {if let Some(conflict) = self.state.conflicts.get(photo) {
+                    html! {<div class="conflict">
+                        <p>{tf("tagging.conflict", &[("names", conflict.names.join(", ")), ("by", conflict.by.clone())])}</p>
+                        <button type="button" onclick=self.link.callback(|_| Msg::Resolve(Resolution::Keep))>{t("tagging.conflict_keep")}</button>
+                        <button type="button" onclick=self.link.callback(|_| Msg::Resolve(Resolution::Replace))>{t("tagging.conflict_replace")}</button>
+                        <button type="button" onclick=self.link.callback(|_| Msg::Resolve(Resolution::Add))>{t("tagging.conflict_add")}</button>
+                    </div>}
+                } else {
+                    html! {}
+                }}
+                <div class="autocomplete">
+                    <input type="text" value={self.state.value.clone()} oninput=self.link.callback( move |v| Msg::UITagValueState(v)) />
+                    {if self.state.show_suggestions {
+                        html! {<ul class="suggestions">
+                            {self.suggestions().into_iter().map(|tag| {
+                                let picked = tag.clone();
+                                html! {<li onclick=self.link.callback(move |_| Msg::PickSuggestion(picked.clone()))>
+                                    <NameAvatar name={tag.clone()} photo={self.state.avatars.get(tag).cloned()} />
+                                </li>}
+                            }).collect::<Html>()}
+                        </ul>}
+                    } else {
+                        html! {}
+                    }}
+                </div>
+                <button type="button" onclick=self.link.callback(move |_| Msg::Save)>{t("tagging.save_next")}</button>
+                <span class="skip">
+                    {t("tagging.skip_next")}
+                    {SkipReason::ALL.iter().map(|reason| {
+                        let reason = *reason;
+                        html! {<button type="button" onclick=self.link.callback(move |_| Msg::Skip(reason))>{reason.label()}</button>}
+                    }).collect::<Html>()}
+                </span>
+                <button type="button" title=t("tagging.hide_hint") onclick=self.link.callback(|_| Msg::Hide)>{t("tagging.hide")}</button>
+                <button type="button" disabled={!self.state.tags.contains(&self.state.value)} onclick=self.link.callback(move |_| Msg::SetAvatar)>{t("tagging.use_as_avatar")}</button>
             </>}
         } else {
             html!{}
@@ -1,20 +1,34 @@
 use std::collections::HashSet;
 use yew::worker::*;
+use crate::i18n::Locale;
+use crate::skip_reason::SkipReason;
+
+#[derive(Clone)]
+pub enum Decision {
+    Name(String),
+    Skip(SkipReason),
+    /// Not a photo of people at all, so it is put out of the way.
+    Hide
+}
 
-pub enum ListToDetailEvents {
-    ToTag(String)
+#[derive(Clone)]
+pub enum DetailToListEvents {
+    /// The photo filename and what the tagger decided about it.
+    Done(String, Decision),
+    /// The photo could not be shown, so there is nothing to decide about it.
+    Broken(String)
 }
 
-pub struct ListToDetailEventBus {
-    link: AgentLink<ListToDetailEventBus>,
+pub struct DetailToListEventBus {
+    link: AgentLink<DetailToListEventBus>,
     subscribers: HashSet<HandlerId>,
 }
 
-impl Agent for ListToDetailEventBus {
+impl Agent for DetailToListEventBus {
     type Reach = Context<Self>;
     type Message = ();
-    type Input = ListToDetailEvents;
-    type Output = String;
+    type Input = DetailToListEvents;
+    type Output = DetailToListEvents;
 
     fn create(link: AgentLink<Self>) -> Self {
         Self {
@@ -26,12 +40,8 @@ impl Agent for ListToDetailEventBus {
     fn update(&mut self, _msg: Self::Message) {}
 
     fn handle_input(&mut self, msg: Self::Input, _id: HandlerId) {
-        match msg {
-            ListToDetailEvents::ToTag(filename) => {
-                for sub in self.subscribers.iter() {
-                    self.link.respond(*sub, filename.clone());
-                }
-            }
+        for sub in self.subscribers.iter() {
+            self.link.respond(*sub, msg.clone());
         }
     }
 
@@ -44,20 +54,25 @@ impl Agent for ListToDetailEventBus {
     }
 }
 
-pub enum DetailToListEvents {
-    Done(Option<String>)
+#[derive(Clone)]
+pub enum SessionEvents {
+    ListLoaded(usize),
+    Saving,
+    Saved,
+    SaveFailed,
+    Skipped
 }
 
-pub struct DetailToListEventBus {
-    link: AgentLink<DetailToListEventBus>,
+pub struct SessionEventBus {
+    link: AgentLink<SessionEventBus>,
     subscribers: HashSet<HandlerId>,
 }
 
-impl Agent for DetailToListEventBus {
+impl Agent for SessionEventBus {
     type Reach = Context<Self>;
     type Message = ();
-    type Input = DetailToListEvents;
-    type Output = Option<String>;
+    type Input = SessionEvents;
+    type Output = SessionEvents;
 
     fn create(link: AgentLink<Self>) -> Self {
         Self {
@@ -69,12 +84,8 @@ impl Agent for DetailToListEventBus {
     fn update(&mut self, _msg: Self::Message) {}
 
     fn handle_input(&mut self, msg: Self::Input, _id: HandlerId) {
-        match msg {
-            DetailToListEvents::Done(done) => {
-                for sub in self.subscribers.iter() {
-                    self.link.respond(*sub, done.clone());
-                }
-            }
+        for sub in self.subscribers.iter() {
+            self.link.respond(*sub, msg.clone());
         }
     }
 
@@ -87,20 +98,57 @@ impl Agent for DetailToListEventBus {
     }
 }
 
-pub enum ItemToListEvents {
-    Next
+#[derive(Clone)]
+pub enum Notification {
+    Info(String),
+    Error(String)
+}
+
+pub struct NotificationEventBus {
+    link: AgentLink<NotificationEventBus>,
+    subscribers: HashSet<HandlerId>,
+}
+
+impl Agent for NotificationEventBus {
+    type Reach = Context<Self>;
+    type Message = ();
+    type Input = Notification;
+    type Output = Notification;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        Self {
+            link,
+            subscribers: HashSet::new()
+        }
+    }
+
+    fn update(&mut self, _msg: Self::Message) {}
+
+    fn handle_input(&mut self, msg: Self::Input, _id: HandlerId) {
+        for sub in self.subscribers.iter() {
+            self.link.respond(*sub, msg.clone());
+        }
+    }
+
+    fn connected(&mut self, id: HandlerId) {
+        self.subscribers.insert(id);
+    }
+
+    fn disconnected(&mut self, id: HandlerId) {
+        self.subscribers.remove(&id);
+    }
 }
 
-pub struct ItemToListEventBus {
-    link: AgentLink<ItemToListEventBus>,
+pub struct LocaleEventBus {
+    link: AgentLink<LocaleEventBus>,
     subscribers: HashSet<HandlerId>,
 }
 
-impl Agent for ItemToListEventBus {
+impl Agent for LocaleEventBus {
     type Reach = Context<Self>;
     type Message = ();
-    type Input = ItemToListEvents;
-    type Output = ();
+    type Input = Locale;
+    type Output = Locale;
 
     fn create(link: AgentLink<Self>) -> Self {
         Self {
@@ -112,12 +160,8 @@ impl Agent for ItemToListEventBus {
     fn update(&mut self, _msg: Self::Message) {}
 
     fn handle_input(&mut self, msg: Self::Input, _id: HandlerId) {
-        match msg {
-            ItemToListEvents::Next => {
-                for sub in self.subscribers.iter() {
-                    self.link.respond(*sub, ());
-                }
-            }
+        for sub in self.subscribers.iter() {
+            self.link.respond(*sub, msg);
         }
     }
 
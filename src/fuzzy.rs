@@ -0,0 +1,67 @@
+/// Scores how well `pattern` fuzzily matches `candidate`.
+///
+/// All characters of the pattern must appear in the candidate in order, ignoring case.
+/// Consecutive runs and matches at the start of the candidate score higher.
+/// Returns `None` when the pattern does not match at all.
+pub fn score(pattern: &str, candidate: &str) -> Option<i32> {
+    let pattern = pattern.to_lowercase().chars().filter(|c| !c.is_whitespace()).collect::<Vec<_>>();
+    if pattern.is_empty() {
+        return Some(0);
+    }
+    let mut score = 0;
+    let mut p = 0;
+    let mut last_match: Option<usize> = None;
+    for (i, c) in candidate.to_lowercase().chars().enumerate() {
+        if p < pattern.len() && c == pattern[p] {
+            score += match last_match {
+                Some(last) if last + 1 == i => 5,
+                _ if i == 0 => 3,
+                _ => 1
+            };
+            last_match = Some(i);
+            p += 1;
+        }
+    }
+    if p == pattern.len() {
+        // Prefer shorter candidates among equally good matches.
+        Some(score * 100 - candidate.chars().count() as i32)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::score;
+
+    #[test]
+    fn prefix_beats_substring_beats_subsequence() {
+        let prefix = score("ann", "Anna").unwrap();
+        let substring = score("ann", "Joanna").unwrap();
+        let subsequence = score("ann", "Alan Nash").unwrap();
+        assert!(prefix > substring);
+        assert!(substring > subsequence);
+    }
+
+    #[test]
+    fn ignores_case_and_whitespace() {
+        assert_eq!(score("AN NA", "anna"), score("anna", "ANNA"));
+    }
+
+    #[test]
+    fn shorter_candidate_wins_a_tie() {
+        assert!(score("li", "Li") > score("li", "Lily"));
+    }
+
+    #[test]
+    fn out_of_order_does_not_match() {
+        assert_eq!(score("na", "Anne"), None);
+        assert_eq!(score("xa", "Anna"), None);
+        assert_eq!(score("aab", "Ab"), None);
+    }
+
+    #[test]
+    fn empty_pattern_matches_everything() {
+        assert_eq!(score(" ", "Anna"), Some(0));
+    }
+}
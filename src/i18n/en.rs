@@ -0,0 +1,140 @@
+pub fn lookup(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "app.title" => "Jian Ai",
+        "nav.tagging" => "Tagging",
+        "nav.new_tag" => "New names",
+        "nav.stats" => "Statistics",
+        "nav.skipped" => "Skipped",
+        "nav.export" => "Export",
+        "nav.hidden" => "Hidden",
+        "layout.language" => "Language",
+        "layout.saving.one" => "Saving {count} photo",
+        "layout.saving.other" => "Saving {count} photos",
+        "layout.failed.one" => "{count} photo failed to save",
+        "layout.failed.other" => "{count} photos failed to save",
+        "layout.retry" => "Retry",
+
+        "errors.fetch" => "Error starting fetch: {error}",
+        "errors.status" => "Server returns error code: {status}",
+        "errors.content" => "Server returns unrecognized data: {error}",
+        "errors.forbidden" => "You are not allowed to open this page.",
+        "save_queue.failed" => "Failed to save {photo}: {error}",
+        "save_queue.conflict" => "{photo} was already named as {names} by {by}. Open it from the list to choose which names to keep.",
+
+        "duration.minutes" => "{minutes} min",
+        "duration.hours_minutes" => "{hours} h {minutes} min",
+        "session.saved" => "Tagged: {count}",
+        "session.skipped" => "Skipped: {count}",
+        "session.failed" => "Failed: {count}",
+        "session.remaining" => "Remaining: {count}",
+        "session.eta" => "ETA: {eta}",
+
+        "photo_list.loading" => "Loading...",
+        "photo_list.filter" => "Filter by filename",
+        "photo_list.folder" => "Folder",
+        "photo_list.taken_from" => "Taken from",
+        "photo_list.taken_to" => "to",
+        "photo_list.sort_listed" => "As listed",
+        "photo_list.sort_taken" => "By date taken",
+        "photo_list.sort_filename" => "By filename",
+        "photo_list.sort_folder" => "By folder",
+        "photo_list.broken_skipped" => "{photo} could not be loaded and was skipped.",
+        "photo_list.reported" => "{photo} was reported as broken.",
+        "photo_list.report_failed" => "Failed to report {photo}.",
+        "photo_list.hide" => "Not a photo of people, hide it",
+        "photo_list.checked.one" => "{count} photo picked",
+        "photo_list.checked.other" => "{count} photos picked",
+        "photo_list.hide_checked" => "Hide picked",
+        "photo_list.clear_checked" => "Clear",
+        "photo_list.conflicts.one" => "{count} photo was named by someone else meanwhile",
+        "photo_list.conflicts.other" => "{count} photos were named by someone else meanwhile",
+        "photo_list.resolve_next" => "Resolve",
+        "photo_list.name_duplicates" => "Name near-duplicates together",
+        "photo_list.duplicates.one" => "{count} near-duplicate",
+        "photo_list.duplicates.other" => "{count} near-duplicates",
+        "photo_list.named_along.one" => "Also named {count} photo as {name}.",
+        "photo_list.named_along.other" => "Also named {count} photos as {name}.",
+        "photo_list.expand_burst.one" => "Show the {count} photo of this burst",
+        "photo_list.expand_burst.other" => "Show the {count} photos of this burst, to name them one by one",
+        "photo_list.collapse_burst.one" => "Collapse the {count} photo of this burst, to name it at once",
+        "photo_list.collapse_burst.other" => "Collapse the {count} photos of this burst, to name them all at once",
+        "folder_tree.all" => "All folders",
+        "photo.locked_by" => "{name} is tagging this photo.",
+        "photo.conflict" => "Named by someone else meanwhile, open it to choose which names to keep.",
+        "photo.broken" => "Cannot load",
+        "photo.report" => "Report",
+        "image_viewer.fit" => "Fit",
+        "exif.title" => "Photo details",
+        "exif.loading" => "Reading photo details...",
+        "exif.none" => "The photo carries no details.",
+        "exif.taken_at" => "Taken at",
+        "exif.camera" => "Camera",
+        "exif.dimensions" => "Size",
+        "exif.orientation" => "Orientation",
+        "exif.location" => "Location",
+        "rotation.failed" => "Failed to save the rotation of {photo}.",
+
+        "tagging.save_next" => "Save & Next",
+        "tagging.skip_next" => "Skip & Next: ",
+        "tagging.use_as_avatar" => "Use as avatar",
+        "tagging.avatar_set" => "This photo is now the avatar of {name}.",
+        "tagging.avatar_failed" => "Failed to set the avatar.",
+        "tagging.conflict" => "Already named as {names} by {by}.",
+        "tagging.conflict_keep" => "Keep theirs",
+        "tagging.conflict_replace" => "Replace with mine",
+        "tagging.conflict_add" => "Add mine",
+        "tagging.rotate_left" => "Rotate left",
+        "tagging.rotate_right" => "Rotate right",
+        "tagging.hide" => "Hide",
+        "tagging.hide_hint" => "Not a photo of people, hide it",
+
+        "skip.no_face" => "No face",
+        "skip.unrecognizable" => "Unrecognizable",
+        "skip.unknown_person" => "Unknown person",
+        "skip.bad_photo" => "Bad photo",
+
+        "new_tag.saving" => "Saving...",
+        "new_tag.save_failed" => "Failed to save.",
+        "new_tag.saved" => "Saved.",
+        "new_tag.label" => "Names (separate multiple names with \",\"):",
+        "new_tag.save" => "Save",
+        "new_tag.loading" => "Loading all names...",
+        "new_tag.load_failed" => "Failed to load the names, please reload the page.",
+        "new_tag.search" => "Search names",
+        "new_tag.sort_name" => "By name",
+        "new_tag.sort_usage" => "By usage",
+        "new_tag.sort_created" => "By creation time",
+
+        "stats.loading" => "Loading statistics...",
+        "stats.load_failed" => "Failed to load the statistics, please reload the page.",
+        "stats.progress" => "Progress",
+        "stats.summary" => "{total} photos in total, {named} named, {unnamed} unnamed, {skipped} skipped, {hidden} hidden ({percent}% named).",
+        "stats.per_name" => "Photos per name",
+        "stats.throughput" => "Speed per session (photos/minute)",
+
+        "login.title" => "Log in",
+        "login.username" => "Username",
+        "login.password" => "Password",
+        "login.submit" => "Log in",
+        "login.wrong_password" => "Wrong username or password.",
+        "login.logout" => "Log out",
+
+        "not_found.title" => "Page not found",
+        "not_found.path" => "{path} does not exist.",
+        "not_found.home" => "Back to home",
+        "export.title" => "Export",
+        "export.description" => "Download every name given to the photos so far.",
+        "export.submit" => "Export",
+        "export.exporting" => "Exporting…",
+        "export.failed" => "Failed to export.",
+        "export.download" => "Save the exported file",
+        "hidden.title" => "Hidden photos",
+        "hidden.loading" => "Loading...",
+        "hidden.load_failed" => "Failed to load the hidden photos.",
+        "hidden.empty" => "No photo is hidden.",
+        "hidden.restore" => "Restore",
+        "hidden.restore_checked" => "Restore picked",
+        "hidden.restore_failed" => "Failed to restore {photo}.",
+        _ => return None
+    })
+}
@@ -0,0 +1,141 @@
+//! Message catalog of the UI strings.
+//!
+//! Messages are looked up by key in the catalog of the current locale, falling back to zh-CN and
+//! then to the key itself. Placeholders are written as `{name}` in the messages. Messages that
+//! depend on a count have `.one` and `.other` variants, picked by the plural rule of the locale.
+
+mod en;
+mod zh_cn;
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use wasm_bindgen::JsValue;
+use yew::format::Text;
+use yew::services::storage::{Area, StorageService};
+
+const STORAGE_KEY: &str = "jian_ai.locale";
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Locale {
+    ZhCn,
+    En
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::ZhCn, Locale::En];
+
+    /// The BCP 47 language tag.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "zh-CN",
+            Locale::En => "en"
+        }
+    }
+
+    /// The name of the locale, in itself.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::ZhCn => "中文",
+            Locale::En => "English"
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Locale> {
+        let code = code.to_lowercase();
+        if code.starts_with("zh") {
+            Some(Locale::ZhCn)
+        } else if code.starts_with("en") {
+            Some(Locale::En)
+        } else {
+            None
+        }
+    }
+
+    fn lookup(&self, key: &str) -> Option<&'static str> {
+        match self {
+            Locale::ZhCn => zh_cn::lookup(key),
+            Locale::En => en::lookup(key)
+        }
+    }
+
+    fn plural_category(&self, n: usize) -> &'static str {
+        match self {
+            Locale::ZhCn => "other",
+            Locale::En => if n == 1 { "one" } else { "other" }
+        }
+    }
+}
+
+thread_local! {
+    static LOCALE: Cell<Locale> = Cell::new(Locale::ZhCn);
+    /// Number formatters by locale and fraction digits, as lists format a number per row.
+    static NUMBER_FORMATS: RefCell<HashMap<(Locale, usize), js_sys::Function>> = RefCell::new(HashMap::new());
+}
+
+/// Picks the locale saved by the switcher, or else the first supported browser language.
+pub fn init() {
+    let stored = StorageService::new(Area::Local).ok()
+        .and_then(|storage| storage.restore::<Text>(STORAGE_KEY).ok())
+        .and_then(|code| Locale::from_code(&code));
+    let navigator = yew::utils::window().navigator();
+    let detected = || navigator.languages().iter()
+        .filter_map(|language| language.as_string())
+        .chain(navigator.language())
+        .find_map(|language| Locale::from_code(&language));
+    apply(stored.or_else(detected).unwrap_or(Locale::ZhCn));
+}
+
+pub fn locale() -> Locale {
+    LOCALE.with(|locale| locale.get())
+}
+
+/// Switches the locale and remembers it for the next visit.
+pub fn set_locale(locale: Locale) {
+    apply(locale);
+    if let Ok(mut storage) = StorageService::new(Area::Local) {
+        storage.store(STORAGE_KEY, Ok(locale.code().to_string()));
+    }
+}
+
+fn apply(locale: Locale) {
+    LOCALE.with(|l| l.set(locale));
+    if let Some(html) = yew::utils::document().document_element() {
+        let _ = html.set_attribute("lang", locale.code());
+    }
+}
+
+/// The message for `key`.
+pub fn t(key: &str) -> String {
+    locale().lookup(key)
+        .or_else(|| Locale::ZhCn.lookup(key))
+        .unwrap_or(key)
+        .to_string()
+}
+
+/// The message for `key` with its placeholders filled.
+pub fn tf(key: &str, args: &[(&str, String)]) -> String {
+    args.iter().fold(t(key), |message, (name, value)| message.replace(&format!("{{{}}}", name), value))
+}
+
+/// The message for `key` in the plural form for `count`, which fills the `{count}` placeholder.
+pub fn tn(key: &str, count: usize, args: &[(&str, String)]) -> String {
+    let key = format!("{}.{}", key, locale().plural_category(count));
+    let mut args = args.to_vec();
+    args.push(("count", number(count as f64, 0)));
+    tf(&key, &args)
+}
+
+/// Formats a number the way the current locale writes it.
+pub fn number(n: f64, fraction_digits: usize) -> String {
+    let locale = locale();
+    let format = NUMBER_FORMATS.with(|formats| formats.borrow_mut().entry((locale, fraction_digits)).or_insert_with(|| {
+        let options = js_sys::Object::new();
+        let digits = JsValue::from_f64(fraction_digits as f64);
+        let _ = js_sys::Reflect::set(&options, &JsValue::from_str("minimumFractionDigits"), &digits);
+        let _ = js_sys::Reflect::set(&options, &JsValue::from_str("maximumFractionDigits"), &digits);
+        js_sys::Intl::NumberFormat::new(&js_sys::Array::of1(&JsValue::from_str(locale.code())), &options).format()
+    }).clone());
+    format.call1(&JsValue::UNDEFINED, &JsValue::from_f64(n)).ok()
+        .and_then(|formatted| formatted.as_string())
+        .unwrap_or_else(|| format!("{:.*}", fraction_digits, n))
+}
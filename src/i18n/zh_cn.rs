@@ -0,0 +1,132 @@
+pub fn lookup(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "app.title" => "兼爱",
+        "nav.tagging" => "标注",
+        "nav.new_tag" => "新名称",
+        "nav.stats" => "统计",
+        "nav.skipped" => "已跳过",
+        "nav.export" => "导出",
+        "nav.hidden" => "已隐藏",
+        "layout.language" => "语言",
+        "layout.saving.other" => "正在保存 {count} 张",
+        "layout.failed.other" => "{count} 张保存失败",
+        "layout.retry" => "重试",
+
+        "errors.fetch" => "无法发出请求：{error}",
+        "errors.status" => "服务器返回错误：{status}",
+        "errors.content" => "服务器返回了无法识别的数据：{error}",
+        "errors.forbidden" => "您无权访问此页面。",
+        "save_queue.failed" => "保存 {photo} 失败：{error}",
+        "save_queue.conflict" => "{photo} 已被 {by} 命名为 {names}。请在列表中打开它，选择保留哪些名称。",
+
+        "duration.minutes" => "{minutes}分钟",
+        "duration.hours_minutes" => "{hours}小时{minutes}分钟",
+        "session.saved" => "已标注：{count}",
+        "session.skipped" => "已跳过：{count}",
+        "session.failed" => "失败：{count}",
+        "session.remaining" => "剩余：{count}",
+        "session.eta" => "预计：{eta}",
+
+        "photo_list.loading" => "正在下载照片列表……",
+        "photo_list.filter" => "按文件名筛选",
+        "photo_list.folder" => "文件夹",
+        "photo_list.taken_from" => "拍摄日期从",
+        "photo_list.taken_to" => "至",
+        "photo_list.sort_listed" => "默认顺序",
+        "photo_list.sort_taken" => "按拍摄日期",
+        "photo_list.sort_filename" => "按文件名",
+        "photo_list.sort_folder" => "按文件夹",
+        "photo_list.broken_skipped" => "{photo} 无法加载，已跳过。",
+        "photo_list.reported" => "已报告 {photo} 损坏。",
+        "photo_list.report_failed" => "报告 {photo} 失败。",
+        "photo_list.hide" => "不是人物照片，隐藏",
+        "photo_list.checked.other" => "已选 {count} 张照片",
+        "photo_list.hide_checked" => "隐藏所选",
+        "photo_list.clear_checked" => "取消选择",
+        "photo_list.conflicts.other" => "{count} 张照片已被他人命名",
+        "photo_list.resolve_next" => "处理",
+        "photo_list.name_duplicates" => "近似重复的照片一并命名",
+        "photo_list.duplicates.other" => "{count} 张近似重复",
+        "photo_list.named_along.other" => "已将另外 {count} 张照片一并命名为 {name}。",
+        "photo_list.expand_burst.other" => "展开这组连拍的 {count} 张照片，逐张命名",
+        "photo_list.collapse_burst.other" => "收起这组连拍的 {count} 张照片，一并命名",
+        "folder_tree.all" => "全部文件夹",
+        "photo.locked_by" => "{name} 正在标记这张照片。",
+        "photo.conflict" => "这张照片已被他人命名，打开它选择保留哪些名称。",
+        "photo.broken" => "无法加载",
+        "photo.report" => "报告",
+        "image_viewer.fit" => "适应窗口",
+        "exif.title" => "照片信息",
+        "exif.loading" => "正在读取照片信息……",
+        "exif.none" => "这张照片没有附带信息。",
+        "exif.taken_at" => "拍摄时间",
+        "exif.camera" => "相机",
+        "exif.dimensions" => "尺寸",
+        "exif.orientation" => "方向",
+        "exif.location" => "位置",
+        "rotation.failed" => "保存 {photo} 的旋转失败。",
+
+        "tagging.save_next" => "保存并下一张",
+        "tagging.skip_next" => "跳过并下一张：",
+        "tagging.use_as_avatar" => "设为头像",
+        "tagging.avatar_set" => "已将此照片设为 {name} 的头像。",
+        "tagging.avatar_failed" => "设置头像失败。",
+        "tagging.conflict" => "已被 {by} 命名为 {names}。",
+        "tagging.conflict_keep" => "保留对方的",
+        "tagging.conflict_replace" => "替换为我的",
+        "tagging.conflict_add" => "添加我的",
+        "tagging.rotate_left" => "向左旋转",
+        "tagging.rotate_right" => "向右旋转",
+        "tagging.hide" => "隐藏",
+        "tagging.hide_hint" => "不是人物照片，隐藏",
+
+        "skip.no_face" => "没有人脸",
+        "skip.unrecognizable" => "无法辨认",
+        "skip.unknown_person" => "不认识",
+        "skip.bad_photo" => "照片损坏",
+
+        "new_tag.saving" => "正在保存……",
+        "new_tag.save_failed" => "保存失败。",
+        "new_tag.saved" => "保存成功。",
+        "new_tag.label" => "名称：（多个输入请用“,”分割）",
+        "new_tag.save" => "保存",
+        "new_tag.loading" => "正在下载所有名称……",
+        "new_tag.load_failed" => "下载名称失败，请刷新页面。",
+        "new_tag.search" => "搜索名称",
+        "new_tag.sort_name" => "按名称",
+        "new_tag.sort_usage" => "按使用次数",
+        "new_tag.sort_created" => "按创建时间",
+
+        "stats.loading" => "正在下载统计数据……",
+        "stats.load_failed" => "下载统计数据失败，请刷新页面。",
+        "stats.progress" => "进度",
+        "stats.summary" => "共 {total} 张照片，已标注 {named} 张，未标注 {unnamed} 张，已跳过 {skipped} 张，已隐藏 {hidden} 张（已标注 {percent}%）。",
+        "stats.per_name" => "每个名称的照片数",
+        "stats.throughput" => "每次标注的速度（张/分钟）",
+
+        "login.title" => "登录",
+        "login.username" => "用户名",
+        "login.password" => "密码",
+        "login.submit" => "登录",
+        "login.wrong_password" => "用户名或密码错误。",
+        "login.logout" => "退出",
+
+        "not_found.title" => "找不到页面",
+        "not_found.path" => "{path} 不存在。",
+        "not_found.home" => "返回首页",
+        "export.title" => "导出",
+        "export.description" => "下载目前为止所有照片的命名结果。",
+        "export.submit" => "导出",
+        "export.exporting" => "正在导出……",
+        "export.failed" => "导出失败。",
+        "export.download" => "保存导出的文件",
+        "hidden.title" => "已隐藏的照片",
+        "hidden.loading" => "加载中……",
+        "hidden.load_failed" => "加载已隐藏的照片失败。",
+        "hidden.empty" => "没有隐藏的照片。",
+        "hidden.restore" => "恢复",
+        "hidden.restore_checked" => "恢复所选",
+        "hidden.restore_failed" => "恢复 {photo} 失败。",
+        _ => return None
+    })
+}
@@ -0,0 +1,246 @@
+//! Photo leases keep concurrent taggers apart: each one claims a batch of the unnamed photos,
+//! renews the claim while working and hands it back when leaving.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::time::Duration;
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use yew::worker::*;
+use yew::callback::Callback;
+use yew::services::fetch::*;
+use yew::services::interval::*;
+use yew::format::*;
+use anyhow::Error;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use crate::apis;
+use crate::live::*;
+
+/// How many photos are claimed at a time.
+const BATCH: usize = 20;
+
+#[derive(Deserialize)]
+pub struct Lease {
+    id: String,
+    photos: Vec<String>,
+    /// Seconds until the lease expires unless renewed.
+    ttl: u64
+}
+
+#[derive(Clone, Default)]
+pub struct Leases {
+    /// The photos leased to this tagger.
+    pub mine: HashSet<String>,
+    /// The photos leased to other taggers, with who holds them.
+    pub others: HashMap<String, String>
+}
+
+pub enum LeaseRequest {
+    /// Leases a batch, unless one is held already.
+    Claim,
+    /// The photo is decided, so it no longer needs to be held.
+    Done(String),
+    Release
+}
+
+pub enum Msg {
+    Claimed(Lease),
+    ClaimErr,
+    Renew,
+    Renewed,
+    /// The renewal did not get through, which the next one may.
+    RenewErr,
+    /// The server no longer knows the lease.
+    Lost,
+    GetOthersOk(HashMap<String, String>),
+    Live(LiveEvent)
+}
+
+pub struct LeaseAgent {
+    link: AgentLink<LeaseAgent>,
+    subscribers: HashSet<HandlerId>,
+    leases: Leases,
+    /// Shared with the unload handler, which has to release the lease by itself.
+    lease_id: Rc<RefCell<Option<String>>>,
+    claiming: bool,
+    _live: Box<dyn Bridge<LiveAgent>>,
+    _interval: Option<IntervalTask>,
+    _claim_task: Option<FetchTask>,
+    _renew_task: Option<FetchTask>,
+    _others_task: Option<FetchTask>,
+    _release_task: Option<FetchTask>,
+    _on_unload: Closure<dyn Fn()>
+}
+
+fn release_url(id: &str) -> String {
+    format!("/apis/release_lease?id={}", utf8_percent_encode(id, NON_ALPHANUMERIC))
+}
+
+impl LeaseAgent {
+    fn broadcast(&self) {
+        for sub in self.subscribers.iter() {
+            self.link.respond(*sub, self.leases.clone());
+        }
+    }
+
+    fn claim(&mut self) {
+        if self.claiming || self.lease_id.borrow().is_some() {
+            return;
+        }
+        self.claiming = true;
+        let req = apis::post(format!("/apis/lease?count={}", BATCH)).body(Nothing).unwrap();
+        let on_done = self.link.callback(move |response: Response<Json<Result<Lease, Error>>>| {
+            let Json(data) = response.into_body();
+            match data {
+                Ok(lease) => Msg::Claimed(lease),
+                Err(_) => Msg::ClaimErr
+            }
+        });
+        self._claim_task = apis::fetch(req, on_done).ok();
+    }
+
+    fn release(&mut self) {
+        self._interval = None;
+        if let Some(id) = self.lease_id.borrow_mut().take() {
+            let req = apis::post(release_url(&id)).body(Nothing).unwrap();
+            self._release_task = apis::fetch(req, Callback::from(|_: Response<Text>| ())).ok();
+        }
+        self.leases.mine.clear();
+    }
+
+    fn get_others(&mut self) {
+        let req = apis::get("/apis/leases").body(Nothing).unwrap();
+        let on_done = self.link.callback(move |response: Response<Json<Result<HashMap<String, String>, Error>>>| {
+            let Json(data) = response.into_body();
+            // Not knowing about the others only risks a conflict, which the server still catches.
+            Msg::GetOthersOk(data.unwrap_or_default())
+        });
+        self._others_task = apis::fetch(req, on_done).ok();
+    }
+}
+
+impl Agent for LeaseAgent {
+    type Reach = Context<Self>;
+    type Message = Msg;
+    type Input = LeaseRequest;
+    type Output = Leases;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        let lease_id: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+        let unload_id = lease_id.clone();
+        let on_unload = Closure::wrap(Box::new(move || {
+            if let Some(id) = unload_id.borrow().as_ref() {
+                apis::post_on_unload(&release_url(id));
+            }
+        }) as Box<dyn Fn()>);
+        let _ = yew::utils::window().add_event_listener_with_callback("beforeunload", on_unload.as_ref().unchecked_ref());
+        let live = LiveAgent::bridge(link.callback(Msg::Live));
+        Self {
+            link,
+            subscribers: HashSet::new(),
+            leases: Leases::default(),
+            lease_id,
+            claiming: false,
+            _live: live,
+            _interval: None,
+            _claim_task: None,
+            _renew_task: None,
+            _others_task: None,
+            _release_task: None,
+            _on_unload: on_unload
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match msg {
+            Msg::Claimed(lease) => {
+                self.claiming = false;
+                *self.lease_id.borrow_mut() = Some(lease.id);
+                self.leases.mine = lease.photos.into_iter().collect();
+                // Renewing at half the lifetime leaves room for a slow or failed request.
+                let every = Duration::from_secs((lease.ttl / 2).max(1));
+                self._interval = Some(IntervalService::spawn(every, self.link.callback(|_| Msg::Renew)));
+                self.get_others();
+                self.broadcast();
+            }
+            Msg::ClaimErr => {
+                self.claiming = false;
+            }
+            Msg::Renew => {
+                if let Some(id) = self.lease_id.borrow().clone() {
+                    let req = apis::post(format!("/apis/renew_lease?id={}", utf8_percent_encode(&id, NON_ALPHANUMERIC))).body(Nothing).unwrap();
+                    let on_done = self.link.callback(move |response: Response<Text>| {
+                        match response.status() {
+                            status if status.is_success() => Msg::Renewed,
+                            StatusCode::NOT_FOUND | StatusCode::CONFLICT | StatusCode::GONE => Msg::Lost,
+                            _ => Msg::RenewErr
+                        }
+                    });
+                    self._renew_task = apis::fetch(req, on_done).ok();
+                }
+                self.get_others();
+            }
+            Msg::Renewed | Msg::RenewErr => {}
+            Msg::Lost => {
+                // The lease has expired, its photos may be someone else's by now.
+                self._interval = None;
+                self.lease_id.borrow_mut().take();
+                self.leases.mine.clear();
+                self.broadcast();
+                self.claim();
+            }
+            Msg::GetOthersOk(others) => {
+                self.leases.others = others.into_iter()
+                    .filter(|(photo, _)| !self.leases.mine.contains(photo))
+                    .collect();
+                self.broadcast();
+            }
+            Msg::Live(LiveEvent::Leased { photos, by }) => {
+                for photo in photos.into_iter().filter(|photo| !self.leases.mine.contains(photo)) {
+                    self.leases.others.insert(photo, by.clone());
+                }
+                self.broadcast();
+            }
+            Msg::Live(LiveEvent::Released { photos }) => {
+                for photo in photos {
+                    self.leases.others.remove(&photo);
+                }
+                self.broadcast();
+            }
+            Msg::Live(_) => {}
+        }
+    }
+
+    fn handle_input(&mut self, msg: Self::Input, _id: HandlerId) {
+        match msg {
+            LeaseRequest::Claim => self.claim(),
+            LeaseRequest::Done(photo) => {
+                if self.leases.mine.remove(&photo) && self.leases.mine.is_empty() {
+                    self.release();
+                    self.claim();
+                }
+                self.broadcast();
+            }
+            LeaseRequest::Release => {
+                self.release();
+                self.broadcast();
+            }
+        }
+    }
+
+    fn connected(&mut self, id: HandlerId) {
+        self.subscribers.insert(id);
+        self.link.respond(id, self.leases.clone());
+    }
+
+    fn disconnected(&mut self, id: HandlerId) {
+        self.subscribers.remove(&id);
+    }
+
+    fn destroy(&mut self) {
+        self.release();
+        let _ = yew::utils::window().remove_event_listener_with_callback("beforeunload", self._on_unload.as_ref().unchecked_ref());
+    }
+}
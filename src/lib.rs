@@ -3,6 +3,20 @@ mod route;
 mod pages;
 mod components;
 mod event_buses;
+mod fuzzy;
+mod skip_reason;
+mod save_queue;
+mod query;
+mod i18n;
+mod apis;
+mod session;
+mod lease;
+mod live;
+mod metadata;
+mod rotation;
+mod prefetch;
+mod phash;
+mod burst;
 
 use wasm_bindgen::prelude::*;
 use yew::prelude::*;
@@ -0,0 +1,147 @@
+//! Changes made by the other taggers, pushed by the server over a WebSocket.
+
+use std::collections::HashSet;
+use std::time::Duration;
+use serde::Deserialize;
+use yew::worker::*;
+use yew::services::websocket::*;
+use yew::services::timeout::*;
+use yew::format::Json;
+use anyhow::Error;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use crate::apis;
+use crate::session::{Session, SessionAgent};
+
+/// The first wait before reconnecting, doubled after every failed attempt up to `MAX_BACKOFF`.
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+#[derive(Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveEvent {
+    NameCreated {
+        name: String
+    },
+    ImageNamed {
+        photo_filename: String,
+        name: String,
+        by: String,
+        /// Seconds since epoch.
+        named_at: u64
+    },
+    Leased {
+        photos: Vec<String>,
+        by: String
+    },
+    Released {
+        photos: Vec<String>
+    }
+}
+
+pub enum Msg {
+    Received(Result<LiveEvent, Error>),
+    /// The status of the socket opened as the given connection.
+    Status(u32, WebSocketStatus),
+    Reconnect,
+    Session(Session)
+}
+
+pub struct LiveAgent {
+    link: AgentLink<LiveAgent>,
+    subscribers: HashSet<HandlerId>,
+    backoff: Duration,
+    socket: Option<WebSocketTask>,
+    reconnect: Option<TimeoutTask>,
+    /// Counts the connections, as a dropped socket still reports being closed.
+    connection: u32,
+    /// The token the socket was opened with, which is opened again once it changes.
+    token: Option<String>,
+    _session: Box<dyn Bridge<SessionAgent>>
+}
+
+impl LiveAgent {
+    fn connect(&mut self) {
+        self.connection += 1;
+        self.token = apis::token();
+        let location = yew::utils::window().location();
+        let scheme = if location.protocol().unwrap_or_default() == "https:" { "wss" } else { "ws" };
+        let mut url = format!("{}://{}/apis/live", scheme, location.host().unwrap_or_default());
+        // Browsers cannot add headers to a WebSocket handshake, so the token goes in the query.
+        if let Some(token) = &self.token {
+            url += &format!("?token={}", utf8_percent_encode(token, NON_ALPHANUMERIC));
+        }
+        let on_message = self.link.callback(|Json(data)| Msg::Received(data));
+        let connection = self.connection;
+        let on_status = self.link.callback(move |status| Msg::Status(connection, status));
+        match WebSocketService::connect_text(&url, on_message, on_status) {
+            Ok(socket) => self.socket = Some(socket),
+            Err(_) => self.schedule_reconnect()
+        }
+    }
+
+    fn schedule_reconnect(&mut self) {
+        self.socket = None;
+        if self.reconnect.is_none() {
+            self.reconnect = Some(TimeoutService::spawn(self.backoff, self.link.callback(|_| Msg::Reconnect)));
+            self.backoff = (self.backoff * 2).min(MAX_BACKOFF);
+        }
+    }
+}
+
+impl Agent for LiveAgent {
+    type Reach = Context<Self>;
+    type Message = Msg;
+    type Input = ();
+    type Output = LiveEvent;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        let mut agent = Self {
+            subscribers: HashSet::new(),
+            backoff: MIN_BACKOFF,
+            socket: None,
+            reconnect: None,
+            connection: 0,
+            token: None,
+            _session: SessionAgent::bridge(link.callback(Msg::Session)),
+            link
+        };
+        agent.connect();
+        agent
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match msg {
+            Msg::Received(Ok(event)) => {
+                for sub in self.subscribers.iter() {
+                    self.link.respond(*sub, event.clone());
+                }
+            }
+            // Events this client does not know about are of no use to it.
+            Msg::Received(Err(_)) => {}
+            Msg::Status(connection, _) if connection != self.connection => {}
+            Msg::Status(_, WebSocketStatus::Opened) => { self.backoff = MIN_BACKOFF }
+            Msg::Status(_, WebSocketStatus::Closed) | Msg::Status(_, WebSocketStatus::Error) => self.schedule_reconnect(),
+            Msg::Reconnect => {
+                self.reconnect = None;
+                self.connect();
+            }
+            // Logging in or out changes the token, and what the server sends over the socket.
+            Msg::Session(_) if apis::token() != self.token => {
+                self.reconnect = None;
+                self.backoff = MIN_BACKOFF;
+                self.connect();
+            }
+            Msg::Session(_) => {}
+        }
+    }
+
+    fn handle_input(&mut self, _msg: Self::Input, _id: HandlerId) {}
+
+    fn connected(&mut self, id: HandlerId) {
+        self.subscribers.insert(id);
+    }
+
+    fn disconnected(&mut self, id: HandlerId) {
+        self.subscribers.remove(&id);
+    }
+}
@@ -0,0 +1,77 @@
+//! What the EXIF data of a photo tells about it, parsed right in the browser.
+
+use std::io::Cursor;
+use exif::{Exif, In, Reader, Tag, Value};
+
+#[derive(Clone, Default, PartialEq)]
+pub struct Metadata {
+    /// As written by the camera, which does not record the time zone.
+    pub taken_at: Option<String>,
+    pub camera: Option<String>,
+    /// The EXIF orientation, 1 to 8, where 1 means upright.
+    pub orientation: Option<u32>,
+    /// Latitude and longitude in degrees.
+    pub gps: Option<(f64, f64)>,
+    pub dimensions: Option<(u32, u32)>
+}
+
+impl Metadata {
+    /// The clockwise degrees and the horizontal mirroring, mirrored first, that show the photo upright.
+    pub fn upright(&self) -> (u32, bool) {
+        match self.orientation {
+            Some(2) => (0, true),
+            Some(3) => (180, false),
+            Some(4) => (180, true),
+            Some(5) => (270, true),
+            Some(6) => (90, false),
+            Some(7) => (90, true),
+            Some(8) => (270, false),
+            _ => (0, false)
+        }
+    }
+}
+
+pub fn parse(bytes: &[u8]) -> Result<Metadata, exif::Error> {
+    let exif = Reader::new().read_from_container(&mut Cursor::new(bytes))?;
+    let camera = match (string(&exif, Tag::Make), string(&exif, Tag::Model)) {
+        // Models often repeat the make already.
+        (Some(make), Some(model)) if model.starts_with(&make) => Some(model),
+        (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+        (make, model) => make.or(model)
+    };
+    let dimensions = uint(&exif, Tag::PixelXDimension).zip(uint(&exif, Tag::PixelYDimension))
+        .or_else(|| uint(&exif, Tag::ImageWidth).zip(uint(&exif, Tag::ImageLength)));
+    Ok(Metadata {
+        taken_at: string(&exif, Tag::DateTimeOriginal).or_else(|| string(&exif, Tag::DateTime)),
+        camera,
+        orientation: uint(&exif, Tag::Orientation),
+        gps: coordinate(&exif, Tag::GPSLatitude, Tag::GPSLatitudeRef, "S")
+            .zip(coordinate(&exif, Tag::GPSLongitude, Tag::GPSLongitudeRef, "W")),
+        dimensions
+    })
+}
+
+fn string(exif: &Exif, tag: Tag) -> Option<String> {
+    match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Ascii(values) => values.first()
+            .map(|v| String::from_utf8_lossy(v).trim_end_matches('\0').trim().to_string())
+            .filter(|s| !s.is_empty()),
+        _ => None
+    }
+}
+
+fn uint(exif: &Exif, tag: Tag) -> Option<u32> {
+    exif.get_field(tag, In::PRIMARY)?.value.get_uint(0)
+}
+
+/// Reads degrees, minutes and seconds, negated when the reference is `negative`.
+fn coordinate(exif: &Exif, tag: Tag, reference: Tag, negative: &str) -> Option<f64> {
+    let degrees = match &exif.get_field(tag, In::PRIMARY)?.value {
+        Value::Rational(dms) if dms.len() >= 3 => dms[0].to_f64() + dms[1].to_f64() / 60.0 + dms[2].to_f64() / 3600.0,
+        _ => return None
+    };
+    if degrees.is_nan() {
+        return None;
+    }
+    Some(if string(exif, reference).as_deref() == Some(negative) { -degrees } else { degrees })
+}
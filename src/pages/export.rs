@@ -0,0 +1,136 @@
+use yew::prelude::*;
+use yew::services::fetch::*;
+use yew::format::*;
+use anyhow::{anyhow, Error};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use crate::apis;
+use crate::i18n::{t, tf};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Json,
+    Csv
+}
+impl Format {
+    fn code(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Csv => "csv"
+        }
+    }
+    fn mime(&self) -> &'static str {
+        match self {
+            Format::Json => "application/json",
+            Format::Csv => "text/csv"
+        }
+    }
+}
+
+struct State {
+    format: Format,
+    exporting: bool,
+    /// The exported content, kept as a data URL so the browser can save it.
+    download: Option<String>,
+    error: Option<String>
+}
+
+pub struct ExportPage {
+    state: State,
+    link: ComponentLink<Self>,
+    _task: Option<FetchTask>
+}
+
+pub enum Msg {
+    Export,
+    ExportOk(String),
+    ExportErr(Error),
+    UIFormatState(ChangeData)
+}
+
+impl Component for ExportPage {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            state: State {
+                format: Format::Json,
+                exporting: false,
+                download: None,
+                error: None
+            },
+            link,
+            _task: None
+        }
+    }
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Export => {
+                self.state.exporting = true;
+                self.state.download = None;
+                self.state.error = None;
+                let req = apis::get(format!("/apis/export?format={}", self.state.format.code())).body(Nothing).unwrap();
+                let on_done = self.link.callback(move |response: Response<Text>| {
+                    if response.status().is_success() {
+                        match response.into_body() {
+                            Ok(content) => Msg::ExportOk(content),
+                            Err(e) => Msg::ExportErr(e)
+                        }
+                    } else {
+                        Msg::ExportErr(anyhow!(tf("errors.status", &[("status", response.status().to_string())])))
+                    }
+                });
+                match apis::fetch(req, on_done) {
+                    Ok(task) => self._task = Some(task),
+                    Err(e) => self.link.send_message(Msg::ExportErr(e))
+                }
+                true
+            }
+            Msg::ExportOk(content) => {
+                self.state.exporting = false;
+                self.state.download = Some(format!("data:{};charset=utf-8,{}", self.state.format.mime(), utf8_percent_encode(&content, NON_ALPHANUMERIC)));
+                true
+            }
+            Msg::ExportErr(e) => {
+                self.state.exporting = false;
+                self.state.error = Some(e.to_string());
+                true
+            }
+            Msg::UIFormatState(v) => {
+                if let ChangeData::Select(select) = v {
+                    self.state.format = if select.value() == "csv" { Format::Csv } else { Format::Json };
+                    self.state.download = None;
+                }
+                true
+            }
+        }
+    }
+    fn change(&mut self, _: Self::Properties) -> ShouldRender { true }
+    fn view(&self) -> Html { html!{<>
+<section>
+    <article>
+        <h2>{t("export.title")}</h2>
+        <p>{t("export.description")}</p>
+        <div class="filters">
+            <select onchange=self.link.callback(Msg::UIFormatState)>
+                <option value="json" selected={self.state.format == Format::Json}>{"JSON"}</option>
+                <option value="csv" selected={self.state.format == Format::Csv}>{"CSV"}</option>
+            </select>
+            <button type="button" disabled=self.state.exporting onclick=self.link.callback(|_| Msg::Export)>{t("export.submit")}</button>
+        </div>
+        {if self.state.exporting {
+            html!{<p>{t("export.exporting")}</p>}
+        } else if let Some(e) = &self.state.error {
+            html!{<>
+                <p>{t("export.failed")}</p>
+                <p>{e}</p>
+            </>}
+        } else if let Some(href) = &self.state.download {
+            html!{<p><a href=href.clone() download=format!("jian_ai.{}", self.state.format.code())>{t("export.download")}</a></p>}
+        } else {
+            html!{}
+        }}
+    </article>
+</section>
+    </>} }
+}
@@ -0,0 +1,156 @@
+use std::collections::{HashMap, HashSet};
+use yew::prelude::*;
+use yew::agent::*;
+use yew::services::fetch::*;
+use yew::format::*;
+use anyhow::Error;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use crate::apis;
+use crate::components::Photo;
+use crate::event_buses::*;
+use crate::i18n::{t, tf, tn};
+
+struct State {
+    photos: Vec<String>,
+    checked: HashSet<String>,
+    getting_photos: bool,
+    get_photos_error: Option<Error>
+}
+
+/// The photos hidden as junk, which can be put back among the ones to tag from here.
+pub struct HiddenPage {
+    state: State,
+    link: ComponentLink<Self>,
+    event_bus_notification: Dispatcher<NotificationEventBus>,
+    _task: Option<FetchTask>,
+    restore_tasks: HashMap<String, FetchTask>
+}
+
+pub enum Msg {
+    Refresh,
+    GetPhotosOk(Vec<String>),
+    GetPhotosErr(Error),
+    Check(String),
+    Restore(Vec<String>),
+    RestoreOk(String),
+    RestoreErr(String)
+}
+
+impl Component for HiddenPage {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(Msg::Refresh);
+        Self {
+            state: State {
+                photos: vec![],
+                checked: HashSet::new(),
+                getting_photos: false,
+                get_photos_error: None
+            },
+            link,
+            event_bus_notification: NotificationEventBus::dispatcher(),
+            _task: None,
+            restore_tasks: HashMap::new()
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Refresh => {
+                self.state.getting_photos = true;
+                let req = apis::get("/apis/hidden_images").body(Nothing).unwrap();
+                let on_done = self.link.callback(move |response: Response<Json<Result<Vec<String>, Error>>>| {
+                    let Json(data) = response.into_body();
+                    match data {
+                        Ok(photos) => Msg::GetPhotosOk(photos),
+                        Err(e) => Msg::GetPhotosErr(e)
+                    }
+                });
+                match apis::fetch(req, on_done) {
+                    Ok(task) => self._task = Some(task),
+                    Err(e) => self.link.send_message(Msg::GetPhotosErr(e))
+                }
+                true
+            }
+            Msg::GetPhotosOk(photos) => {
+                self.state.getting_photos = false;
+                self.state.photos = photos;
+                true
+            }
+            Msg::GetPhotosErr(e) => {
+                self.state.getting_photos = false;
+                self.state.get_photos_error = Some(e);
+                true
+            }
+            Msg::Check(photo) => {
+                if !self.state.checked.remove(&photo) {
+                    self.state.checked.insert(photo);
+                }
+                true
+            }
+            Msg::Restore(photos) => {
+                for photo in photos {
+                    let req = apis::post(format!("/apis/restore_image?photo_filename={}", utf8_percent_encode(&photo, NON_ALPHANUMERIC))).body(Nothing).unwrap();
+                    let filename = photo.clone();
+                    let on_done = self.link.callback(move |response: Response<Text>| {
+                        if response.status().is_success() { Msg::RestoreOk(filename.clone()) } else { Msg::RestoreErr(filename.clone()) }
+                    });
+                    match apis::fetch(req, on_done) {
+                        Ok(task) => { self.restore_tasks.insert(photo, task); }
+                        Err(_) => self.link.send_message(Msg::RestoreErr(photo))
+                    }
+                }
+                true
+            }
+            Msg::RestoreOk(photo) => {
+                self.restore_tasks.remove(&photo);
+                self.state.checked.remove(&photo);
+                self.state.photos.retain(|p| *p != photo);
+                true
+            }
+            Msg::RestoreErr(photo) => {
+                self.restore_tasks.remove(&photo);
+                self.event_bus_notification.send(Notification::Error(tf("hidden.restore_failed", &[("photo", photo)])));
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender { true }
+
+    fn view(&self) -> Html { html!{<>
+<section>
+    <article>
+        <h2>{t("hidden.title")}</h2>
+        {if self.state.getting_photos {
+            html!{<p>{t("hidden.loading")}</p>}
+        } else if let Some(e) = &self.state.get_photos_error {
+            html!{<>
+                <p>{t("hidden.load_failed")}</p>
+                <p>{e}</p>
+            </>}
+        } else if self.state.photos.is_empty() {
+            html!{<p>{t("hidden.empty")}</p>}
+        } else {
+            let checked = self.state.checked.iter().cloned().collect::<Vec<_>>();
+            html!{<>
+                <div class="batch">
+                    <span>{tn("photo_list.checked", checked.len(), &[])}</span>
+                    <button type="button" disabled=checked.is_empty() onclick=self.link.callback(move |_| Msg::Restore(checked.clone()))>{t("hidden.restore_checked")}</button>
+                </div>
+                <ul class="grid">{self.state.photos.iter().map(|photo| {
+                    let (check, restore) = (photo.clone(), photo.clone());
+                    html!{<li>
+                        <input type="checkbox" checked=self.state.checked.contains(photo) onclick=self.link.callback(move |_| Msg::Check(check.clone())) />
+                        <Photo photo=photo.clone() onclick=self.link.callback(Msg::Check) />
+                        <button type="button" disabled=self.restore_tasks.contains_key(photo) onclick=self.link.callback(move |_| Msg::Restore(vec![restore.clone()]))>{t("hidden.restore")}</button>
+                    </li>}
+                }).collect::<Html>()}</ul>
+            </>}
+        }}
+    </article>
+</section>
+    </>} }
+}
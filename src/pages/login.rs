@@ -0,0 +1,131 @@
+use serde::Deserialize;
+use serde_json::json;
+use yew::prelude::*;
+use yew::agent::*;
+use yew::services::fetch::*;
+use yew::format::*;
+use anyhow::Error;
+use yew_router::prelude::*;
+use yew_router::agent::RouteRequest;
+use crate::apis;
+use crate::i18n::{t, tf};
+use crate::query;
+use crate::session::*;
+
+#[derive(Deserialize)]
+pub struct LoginResponse {
+    token: String,
+    user: User
+}
+
+struct State {
+    username: String,
+    password: String,
+    logging_in: bool,
+    login_error: Option<String>
+}
+
+pub struct LoginPage {
+    state: State,
+    link: ComponentLink<Self>,
+    router: RouteAgentDispatcher,
+    session: Dispatcher<SessionAgent>,
+    _task: Option<FetchTask>
+}
+
+pub enum Msg {
+    Login,
+    LoginOk(LoginResponse),
+    LoginErr(String),
+    UIUsernameValueState(InputData),
+    UIPasswordValueState(InputData)
+}
+
+impl Component for LoginPage {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        Self {
+            state: State {
+                username: "".to_string(),
+                password: "".to_string(),
+                logging_in: false,
+                login_error: None
+            },
+            link,
+            router: RouteAgentDispatcher::new(),
+            session: SessionAgent::dispatcher(),
+            _task: None
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Login => {
+                self.state.logging_in = true;
+                self.state.login_error = None;
+                let body = json!({"username": self.state.username, "password": self.state.password});
+                let req = Request::post("/apis/login").header("Content-Type", "application/json").body(Json(&body)).unwrap();
+                let on_done = self.link.callback(move |response: Response<Json<Result<LoginResponse, Error>>>| {
+                    let status = response.status();
+                    let Json(data) = response.into_body();
+                    match data {
+                        Ok(login) if status.is_success() => Msg::LoginOk(login),
+                        _ if status == StatusCode::UNAUTHORIZED => Msg::LoginErr(t("login.wrong_password")),
+                        _ => Msg::LoginErr(tf("errors.status", &[("status", status.to_string())]))
+                    }
+                });
+                // Not through `apis::fetch`, as a 401 here means wrong credentials instead of an expired session.
+                match FetchService::fetch(req, on_done) {
+                    Ok(task) => self._task = Some(task),
+                    Err(e) => self.link.send_message(Msg::LoginErr(tf("errors.fetch", &[("error", e.to_string())])))
+                }
+                true
+            }
+            Msg::LoginOk(login) => {
+                self.state.logging_in = false;
+                apis::set_token(Some(login.token));
+                self.session.send(SessionRequest::LoggedIn(login.user));
+                let next = query::current().get("next").cloned().filter(|next| next.starts_with('/')).unwrap_or_else(|| "/".to_string());
+                self.router.send(RouteRequest::ReplaceRoute(yew_router::route::Route::new_no_state(next)));
+                true
+            }
+            Msg::LoginErr(e) => {
+                self.state.logging_in = false;
+                self.state.login_error = Some(e);
+                true
+            }
+            Msg::UIUsernameValueState(v) => {
+                self.state.username = v.value;
+                false
+            }
+            Msg::UIPasswordValueState(v) => {
+                self.state.password = v.value;
+                false
+            }
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender { true }
+
+    fn view(&self) -> Html { html!{<>
+<section>
+    <article>
+        <form class="login" onsubmit=self.link.callback(|e: FocusEvent| { e.prevent_default(); Msg::Login })>
+            <h2>{t("login.title")}</h2>
+            {if let Some(e) = &self.state.login_error {
+                html!{<p class="error">{e}</p>}
+            } else {
+                html!{}
+            }}
+            <label for="username">{t("login.username")}</label>
+            <input id="username" type="text" autocomplete="username" value={self.state.username.clone()} oninput=self.link.callback(Msg::UIUsernameValueState) />
+            <label for="password">{t("login.password")}</label>
+            <input id="password" type="password" autocomplete="current-password" value={self.state.password.clone()} oninput=self.link.callback(Msg::UIPasswordValueState) />
+            <button type="submit" disabled=self.state.logging_in>{t("login.submit")}</button>
+        </form>
+    </article>
+</section>
+    </>} }
+}
@@ -1,5 +1,17 @@
 pub mod tagging;
 pub mod new_tag;
+pub mod stats;
+pub mod skipped;
+pub mod not_found;
+pub mod login;
+pub mod export;
+pub mod hidden;
 
 pub use tagging::*;
 pub use new_tag::*;
+pub use stats::*;
+pub use skipped::*;
+pub use not_found::*;
+pub use login::*;
+pub use export::*;
+pub use hidden::*;
@@ -1,15 +1,49 @@
+use std::collections::HashMap;
+use serde::Deserialize;
 use yew::prelude::*;
 use yew::services::fetch::*;
 use yew::format::*;
 use anyhow::Error;
 use yew::services::console::ConsoleService;
 use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use crate::apis;
+use crate::components::{fetch_avatars, NameAvatar};
+use crate::fuzzy;
+use crate::live::*;
+use crate::i18n::{number, t, tf};
 
 /// https://url.spec.whatwg.org/#fragment-percent-encode-set
 const FRAGMENT: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'<').add(b'>').add(b'`');
 
+#[derive(Deserialize)]
+pub struct NameUsage {
+    count: u32,
+    /// Seconds since epoch.
+    created_at: u64
+}
+
+#[derive(PartialEq)]
+enum SortBy {
+    Name,
+    Usage,
+    Created
+}
+impl SortBy {
+    fn from_value(v: &str) -> Self {
+        match v {
+            "usage" => SortBy::Usage,
+            "created" => SortBy::Created,
+            _ => SortBy::Name
+        }
+    }
+}
+
 struct State {
     tags: Vec<String>,
+    avatars: HashMap<String, String>,
+    usages: HashMap<String, NameUsage>,
+    search: String,
+    sort_by: SortBy,
     new_tag: String,
     getting_tags: bool,
     get_tags_error: Option<FetchErrors>,
@@ -21,7 +55,10 @@ struct State {
 pub struct NewTagPage {
     state: State,
     link: ComponentLink<Self>,
-    _task: Option<FetchTask>
+    _task: Option<FetchTask>,
+    _avatars_task: Option<FetchTask>,
+    _usages_task: Option<FetchTask>,
+    _live: Box<dyn Bridge<LiveAgent>>
 }
 
 pub enum Msg {
@@ -31,7 +68,14 @@ pub enum Msg {
     GetTags,
     GetTagsOk(Vec<String>),
     GetTagsErr(FetchErrors),
-    UINewTagValueState(ChangeData)
+    GetAvatars,
+    GetAvatarsOk(HashMap<String, String>),
+    GetUsages,
+    GetUsagesOk(HashMap<String, NameUsage>),
+    UISearchValueState(InputData),
+    UISortByState(ChangeData),
+    UINewTagValueState(ChangeData),
+    Live(LiveEvent)
 }
 
 pub enum FetchErrors {
@@ -42,9 +86,9 @@ pub enum FetchErrors {
 impl std::fmt::Display for FetchErrors {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            FetchErrors::FetchError(e) => write!(f, "Error starting fetch: {}", e),
-            FetchErrors::RequestError(s) => write!(f, "Server returns error code: {}", s),
-            FetchErrors::ContentError(e) => write!(f, "Server returns unrecognized data: {}", e)
+            FetchErrors::FetchError(e) => write!(f, "{}", tf("errors.fetch", &[("error", e.to_string())])),
+            FetchErrors::RequestError(s) => write!(f, "{}", tf("errors.status", &[("status", s.to_string())])),
+            FetchErrors::ContentError(e) => write!(f, "{}", tf("errors.content", &[("error", e.to_string())]))
         }
     }
 }
@@ -55,9 +99,15 @@ impl Component for NewTagPage {
 
     fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
         link.send_message(Msg::GetTags);
+        link.send_message(Msg::GetAvatars);
+        link.send_message(Msg::GetUsages);
         Self {
             state: State {
                 tags: vec![],
+                avatars: HashMap::new(),
+                usages: HashMap::new(),
+                search: "".to_string(),
+                sort_by: SortBy::Name,
                 new_tag: "".to_string(),
                 getting_tags: false,
                 get_tags_error: None,
@@ -65,15 +115,18 @@ impl Component for NewTagPage {
                 save_tag_error: None,
                 show_save_msg: false
             },
+            _live: LiveAgent::bridge(link.callback(Msg::Live)),
             link,
-            _task: None
+            _task: None,
+            _avatars_task: None,
+            _usages_task: None
         }
     }
     fn update(&mut self, msg: Self::Message) -> ShouldRender {
         match msg {
             Msg::GetTags => {
                 self.state.getting_tags = true;
-                let req = Request::get("/apis/names").body(Nothing).unwrap();
+                let req = apis::get("/apis/names").body(Nothing).unwrap();
                 let on_done = self.link.callback(move |response: Response<Json<Result<Vec<String>, Error>>>| {
                     if response.status().is_success() {
                         let Json(data) = response.into_body();
@@ -85,7 +138,7 @@ impl Component for NewTagPage {
                         Msg::GetTagsErr(FetchErrors::RequestError(response.status()))
                     }
                 });
-                match FetchService::fetch(req, on_done) {
+                match apis::fetch(req, on_done) {
                     Ok(task) => self._task = Some(task),
                     Err(e) => self.link.send_message(Msg::GetTagsErr(FetchErrors::FetchError(e)))
                 }
@@ -101,10 +154,42 @@ impl Component for NewTagPage {
                 self.state.get_tags_error = Some(e);
                 true
             }
+            Msg::GetAvatars => {
+                self._avatars_task = fetch_avatars(self.link.callback(Msg::GetAvatarsOk));
+                false
+            }
+            Msg::GetAvatarsOk(avatars) => {
+                self.state.avatars = avatars;
+                true
+            }
+            Msg::GetUsages => {
+                let req = apis::get("/apis/name_usages").body(Nothing).unwrap();
+                let on_done = self.link.callback(move |response: Response<Json<Result<HashMap<String, NameUsage>, Error>>>| {
+                    let Json(data) = response.into_body();
+                    // Without usages the grid can still be searched and sorted by name.
+                    Msg::GetUsagesOk(data.unwrap_or_default())
+                });
+                self._usages_task = apis::fetch(req, on_done).ok();
+                false
+            }
+            Msg::GetUsagesOk(usages) => {
+                self.state.usages = usages;
+                true
+            }
+            Msg::UISearchValueState(v) => {
+                self.state.search = v.value;
+                true
+            }
+            Msg::UISortByState(v) => {
+                if let ChangeData::Select(select) = v {
+                    self.state.sort_by = SortBy::from_value(&select.value());
+                }
+                true
+            }
             Msg::SaveTag => {
                 self.state.saving_tag = true;
                 self.state.show_save_msg = true;
-                match Request::post(format!("/apis/new_names?names={}", utf8_percent_encode(&self.state.new_tag, FRAGMENT))).body(Nothing) {
+                match apis::post(format!("/apis/new_names?names={}", utf8_percent_encode(&self.state.new_tag, FRAGMENT))).body(Nothing) {
                     Ok(req) => {
                         let on_done = self.link.callback(move |response: Response<Result<String, Error>>| {
                             if response.status().is_success() {
@@ -113,7 +198,7 @@ impl Component for NewTagPage {
                                 Msg::SaveTagErr(FetchErrors::RequestError(response.status()))
                             }
                         });
-                        match FetchService::fetch(req, on_done) {
+                        match apis::fetch(req, on_done) {
                             Ok(task) => self._task = Some(task),
                             Err(e) => self.link.send_message(Msg::GetTagsErr(FetchErrors::FetchError(e)))
                         }
@@ -129,6 +214,7 @@ impl Component for NewTagPage {
                 self.state.saving_tag = false;
                 self.state.new_tag = "".to_string();
                 self.link.send_message(Msg::GetTags);
+                self.link.send_message(Msg::GetUsages);
                 true
             }
             Msg::SaveTagErr(e) => {
@@ -136,6 +222,17 @@ impl Component for NewTagPage {
                 self.state.save_tag_error = Some(e);
                 true
             }
+            Msg::Live(LiveEvent::NameCreated { name }) if !self.state.tags.contains(&name) => {
+                self.state.tags.push(name);
+                true
+            }
+            Msg::Live(LiveEvent::ImageNamed { name, .. }) => {
+                if let Some(usage) = self.state.usages.get_mut(&name) {
+                    usage.count += 1;
+                }
+                true
+            }
+            Msg::Live(_) => { false }
             Msg::UINewTagValueState(v) => {
                 match v {
                     ChangeData::Value(v) => { self.state.new_tag = v }
@@ -148,50 +245,73 @@ impl Component for NewTagPage {
         }
     }
     fn change(&mut self, _: Self::Properties) -> ShouldRender { true }
-    fn view(&self) -> Html {html!{<>
-<header> <h1>{"兼爱"}</h1> </header>
-<nav class="hnav"><ul>
-    <li><a href="/tagging">{"标注"}</a></li>
-    <li><a href="/new_tag">{"新名称"}</a></li>
-</ul></nav>
+    fn view(&self) -> Html {
+        let usage = |tag: &String| self.state.usages.get(tag);
+        let mut tags = self.state.tags.iter()
+            .filter_map(|tag| fuzzy::score(&self.state.search, tag).map(|score| (tag, score)))
+            .collect::<Vec<_>>();
+        match self.state.sort_by {
+            SortBy::Name => tags.sort_by(|(a, _), (b, _)| a.cmp(b)),
+            SortBy::Usage => tags.sort_by_key(|(tag, _)| std::cmp::Reverse(usage(tag).map(|u| u.count).unwrap_or(0))),
+            SortBy::Created => tags.sort_by_key(|(tag, _)| std::cmp::Reverse(usage(tag).map(|u| u.created_at).unwrap_or(0)))
+        }
+        if !self.state.search.trim().is_empty() {
+            // The best matches go first; the chosen order breaks ties since the sort is stable.
+            tags.sort_by_key(|(_, score)| std::cmp::Reverse(*score));
+        }
+        html!{<>
 <section>
     <article>
         <div>
             {if self.state.show_save_msg {
                 if self.state.saving_tag {
-                    html!{<div class="mask"><h1>{"正在保存……"}</h1></div>}
+                    html!{<div class="mask"><h1>{t("new_tag.saving")}</h1></div>}
                 } else {
                     if let Some(e) = &self.state.save_tag_error {
                         html!{<>
-                            <p>{"保存失败。"}</p>
+                            <p>{t("new_tag.save_failed")}</p>
                             <p>{e}</p>
                         </>}
                     } else {
-                        html!{<p>{"保存成功。"}</p>}
+                        html!{<p>{t("new_tag.saved")}</p>}
                     }
                 }
             } else {
                 html!{}
             }}
-            <label for="tag">{"名称：（多个输入请用“,”分割）"}</label>
+            <label for="tag">{t("new_tag.label")}</label>
             <input id="tag" type="text" value={self.state.new_tag.clone()} onchange=self.link.callback( move |v| Msg::UINewTagValueState(v)) />
-            <button type="button" onclick=self.link.callback(move |_| Msg::SaveTag)>{"Save"}</button>
+            <button type="button" onclick=self.link.callback(move |_| Msg::SaveTag)>{t("new_tag.save")}</button>
         </div>
         <hr />
         {if self.state.getting_tags {
-            html!{<p>{"正在下载所有名称……"}</p>}
+            html!{<p>{t("new_tag.loading")}</p>}
         } else {
             if let Some(e) = &self.state.get_tags_error {
                 html!{<>
-                    <p>{"下载名称失败，请刷新页面。"}</p>
+                    <p>{t("new_tag.load_failed")}</p>
                     <p>{e}</p>
                 </>}
             } else {
-                html!{<div class="grid">{self.state.tags.iter().map(|tag| html!{<div>{tag}</div>}).collect::<Html>()}</div>}
+                html!{<>
+                    <div class="filters">
+                        <input type="search" placeholder=t("new_tag.search") value={self.state.search.clone()} oninput=self.link.callback(Msg::UISearchValueState) />
+                        <select onchange=self.link.callback(Msg::UISortByState)>
+                            <option value="name" selected={self.state.sort_by == SortBy::Name}>{t("new_tag.sort_name")}</option>
+                            <option value="usage" selected={self.state.sort_by == SortBy::Usage}>{t("new_tag.sort_usage")}</option>
+                            <option value="created" selected={self.state.sort_by == SortBy::Created}>{t("new_tag.sort_created")}</option>
+                        </select>
+                        <span>{format!("{} / {}", number(tags.len() as f64, 0), number(self.state.tags.len() as f64, 0))}</span>
+                    </div>
+                    <div class="grid">{tags.into_iter().map(|(tag, _)| html!{<div>
+                        <NameAvatar name={tag.clone()} photo={self.state.avatars.get(tag).cloned()} />
+                        <span class="count">{number(usage(tag).map(|u| u.count).unwrap_or(0) as f64, 0)}</span>
+                    </div>}).collect::<Html>()}</div>
+                </>}
             }
         }}
     </article>
 </section>
-<footer>{"Magicloud"}</footer>
-    </>}}
+        </>}
+    }
 }
@@ -0,0 +1,40 @@
+use yew::prelude::*;
+use yew_router::prelude::*;
+use crate::i18n::{t, tf};
+use crate::route::Route;
+
+pub struct NotFoundPage {
+    props: Props
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    /// The path that did not match any route, if known.
+    #[prop_or_default]
+    pub path: Option<String>
+}
+
+impl Component for NotFoundPage {
+    type Message = ();
+    type Properties = Props;
+
+    fn create(props: Self::Properties, _link: ComponentLink<Self>) -> Self { Self { props } }
+    fn update(&mut self, _msg: Self::Message) -> ShouldRender { false }
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        self.props = props;
+        true
+    }
+    fn view(&self) -> Html { html!{<>
+<section>
+    <article>
+        <h2>{t("not_found.title")}</h2>
+        {if let Some(path) = &self.props.path {
+            html!{<p>{tf("not_found.path", &[("path", path.clone())])}</p>}
+        } else {
+            html!{}
+        }}
+        <p><RouterAnchor<Route> route=Route::Home>{t("not_found.home")}</RouterAnchor<Route>></p>
+    </article>
+</section>
+    </>} }
+}
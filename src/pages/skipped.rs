@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use serde_json::to_string;
+use yew::prelude::*;
+use yew::services::fetch::*;
+use yew::format::*;
+use anyhow::Error;
+use crate::apis;
+use crate::components::*;
+use crate::skip_reason::SkipReason;
+
+struct State {
+    reason: SkipReason,
+    photo: Option<String>,
+    tags: Vec<String>,
+    avatars: HashMap<String, String>
+}
+
+/// Review of the photos skipped with a given reason, which can be named or skipped again from here.
+pub struct SkippedPage {
+    state: State,
+    link: ComponentLink<Self>,
+    _task: Option<FetchTask>,
+    _avatars_task: Option<FetchTask>
+}
+
+pub enum Msg {
+    SelectReason(SkipReason),
+    GetTags,
+    GetTagsOk(Vec<String>),
+    GetAvatars,
+    GetAvatarsOk(HashMap<String, String>),
+    ToTag((String, usize))
+}
+
+impl Component for SkippedPage {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(Msg::GetTags);
+        link.send_message(Msg::GetAvatars);
+        Self {
+            state: State {
+                reason: SkipReason::NoFace,
+                photo: None,
+                tags: vec![],
+                avatars: HashMap::new()
+            },
+            link,
+            _task: None,
+            _avatars_task: None
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::SelectReason(reason) => {
+                self.state.reason = reason;
+                self.state.photo = None;
+                true
+            }
+            Msg::GetTags => {
+                let req = apis::get("/apis/names").body(Nothing).unwrap();
+                let on_done = self.link.callback(move |response: Response<Json<Result<Vec<String>, Error>>>| {
+                    let Json(data) = response.into_body();
+                    Msg::GetTagsOk(data.unwrap_or_default())
+                });
+                self._task = apis::fetch(req, on_done).ok();
+                false
+            }
+            Msg::GetTagsOk(tags) => {
+                self.state.tags = tags;
+                true
+            }
+            Msg::GetAvatars => {
+                self._avatars_task = fetch_avatars(self.link.callback(Msg::GetAvatarsOk));
+                false
+            }
+            Msg::GetAvatarsOk(avatars) => {
+                self.state.avatars = avatars;
+                true
+            }
+            Msg::ToTag((filename, _)) => {
+                self.state.photo = Some(filename);
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender { true }
+
+    fn view(&self) -> Html { html!{<>
+<section>
+    <nav>
+        <ul class="tabs">
+            {SkipReason::ALL.iter().map(|reason| {
+                let reason = *reason;
+                html!{<li class={if reason == self.state.reason { "active" } else { "" }}>
+                    <a onclick=self.link.callback(move |_| Msg::SelectReason(reason))>{reason.label()}</a>
+                </li>}
+            }).collect::<Html>()}
+        </ul>
+        <PhotoList onclick=self.link.callback(Msg::ToTag) selected=self.state.photo.clone() source={format!("/apis/skipped_images?reason={}", self.state.reason.to_string())} />
+    </nav>
+    <article>
+        <Tagging tags_json={to_string(&self.state.tags).unwrap()} avatars_json={to_string(&self.state.avatars).unwrap()} photo=self.state.photo.clone() />
+    </article>
+</section>
+    </>} }
+}
@@ -0,0 +1,234 @@
+use std::collections::{HashMap, HashSet};
+use serde::Deserialize;
+use yew::prelude::*;
+use yew::services::fetch::*;
+use yew::format::*;
+use anyhow::Error;
+use crate::apis;
+use crate::components::BarChart;
+use crate::i18n::{self, number, t, tf};
+use crate::live::*;
+use crate::skip_reason::SkipReason;
+
+/// Namings further apart than this belong to different tagging sessions.
+const SESSION_GAP_SECS: u64 = 30 * 60;
+
+#[derive(Deserialize, Clone)]
+pub struct NamedImage {
+    photo_filename: String,
+    name: String,
+    /// Seconds since epoch.
+    named_at: u64
+}
+
+struct Session {
+    start: u64,
+    count: usize,
+    photos_per_minute: f64
+}
+
+struct State {
+    unnamed: Option<Vec<String>>,
+    named: Option<Vec<NamedImage>>,
+    /// The photos skipped for each reason, as they come in. Skipped and hidden photos are left
+    /// out of the unnamed list but still count towards the total.
+    skipped: Vec<Vec<String>>,
+    hidden: Option<Vec<String>>,
+    get_stats_error: Option<Error>
+}
+
+pub struct StatsPage {
+    state: State,
+    link: ComponentLink<Self>,
+    _unnamed_task: Option<FetchTask>,
+    _named_task: Option<FetchTask>,
+    _skipped_tasks: Vec<FetchTask>,
+    _hidden_task: Option<FetchTask>,
+    _live: Box<dyn Bridge<LiveAgent>>
+}
+
+pub enum Msg {
+    Refresh,
+    GetUnnamedOk(Vec<String>),
+    GetNamedOk(Vec<NamedImage>),
+    GetSkippedOk(Vec<String>),
+    GetHiddenOk(Vec<String>),
+    GetStatsErr(Error),
+    Live(LiveEvent)
+}
+
+/// Splits the naming log into sessions separated by idle gaps.
+fn sessions(named: &[NamedImage]) -> Vec<Session> {
+    let mut times = named.iter().map(|n| n.named_at).collect::<Vec<_>>();
+    times.sort_unstable();
+    let mut sessions: Vec<(u64, u64, usize)> = vec![];
+    for t in times {
+        match sessions.last_mut() {
+            Some((_, end, count)) if t - *end <= SESSION_GAP_SECS => {
+                *end = t;
+                *count += 1;
+            }
+            _ => sessions.push((t, t, 1))
+        }
+    }
+    sessions.into_iter().map(|(start, end, count)| Session {
+        start,
+        count,
+        // A session of a single naming still took about a minute of work.
+        photos_per_minute: count as f64 / ((end - start) as f64 / 60.0).max(1.0)
+    }).collect()
+}
+
+fn format_time(secs: u64) -> String {
+    let date = js_sys::Date::new(&wasm_bindgen::JsValue::from_f64(secs as f64 * 1000.0));
+    String::from(date.to_locale_string(i18n::locale().code(), &wasm_bindgen::JsValue::UNDEFINED))
+}
+
+impl Component for StatsPage {
+    type Message = Msg;
+    type Properties = ();
+
+    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+        link.send_message(Msg::Refresh);
+        Self {
+            state: State {
+                unnamed: None,
+                named: None,
+                skipped: vec![],
+                hidden: None,
+                get_stats_error: None
+            },
+            _live: LiveAgent::bridge(link.callback(Msg::Live)),
+            link,
+            _unnamed_task: None,
+            _named_task: None,
+            _skipped_tasks: vec![],
+            _hidden_task: None
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) -> ShouldRender {
+        match msg {
+            Msg::Refresh => {
+                self.state.skipped.clear();
+                self._unnamed_task = Some(self.fetch_list("/apis/unnamed_images".to_string(), Msg::GetUnnamedOk));
+                self._skipped_tasks = SkipReason::ALL.iter()
+                    .map(|reason| self.fetch_list(format!("/apis/skipped_images?reason={}", reason.to_string()), Msg::GetSkippedOk))
+                    .collect();
+                self._hidden_task = Some(self.fetch_list("/apis/hidden_images".to_string(), Msg::GetHiddenOk));
+                let req = apis::get("/apis/named_images").body(Nothing).unwrap();
+                let on_done = self.link.callback(move |response: Response<Json<Result<Vec<NamedImage>, Error>>>| {
+                    let Json(data) = response.into_body();
+                    match data {
+                        Ok(named) => Msg::GetNamedOk(named),
+                        Err(e) => Msg::GetStatsErr(e)
+                    }
+                });
+                self._named_task = Some(apis::fetch(req, on_done).unwrap());
+                true
+            }
+            Msg::GetUnnamedOk(photos) => {
+                self.state.unnamed = Some(photos);
+                true
+            }
+            Msg::GetNamedOk(named) => {
+                self.state.named = Some(named);
+                true
+            }
+            Msg::GetSkippedOk(photos) => {
+                self.state.skipped.push(photos);
+                true
+            }
+            Msg::GetHiddenOk(photos) => {
+                self.state.hidden = Some(photos);
+                true
+            }
+            Msg::Live(LiveEvent::ImageNamed { photo_filename, name, named_at, .. }) => {
+                if let Some(unnamed) = self.state.unnamed.as_mut() {
+                    unnamed.retain(|photo| *photo != photo_filename);
+                }
+                if let Some(named) = self.state.named.as_mut() {
+                    named.push(NamedImage { photo_filename, name, named_at });
+                }
+                true
+            }
+            Msg::Live(_) => { false }
+            Msg::GetStatsErr(e) => {
+                self.state.get_stats_error = Some(e);
+                true
+            }
+        }
+    }
+
+    fn change(&mut self, _: Self::Properties) -> ShouldRender { true }
+
+    fn view(&self) -> Html { html!{<>
+<section>
+    <article>
+        {if let Some(e) = &self.state.get_stats_error {
+            html!{<>
+                <p>{t("stats.load_failed")}</p>
+                <p>{e}</p>
+            </>}
+        } else if let (Some(unnamed), Some(named), Some(hidden), true) = (&self.state.unnamed, &self.state.named, &self.state.hidden, self.state.skipped.len() == SkipReason::ALL.len()) {
+            self.view_stats(unnamed, named, hidden)
+        } else {
+            html!{<p>{t("stats.loading")}</p>}
+        }}
+    </article>
+</section>
+    </>} }
+}
+
+impl StatsPage {
+    fn fetch_list(&self, uri: String, ok: fn(Vec<String>) -> Msg) -> FetchTask {
+        let req = apis::get(uri).body(Nothing).unwrap();
+        let on_done = self.link.callback(move |response: Response<Json<Result<Vec<String>, Error>>>| {
+            let Json(data) = response.into_body();
+            match data {
+                Ok(photos) => ok(photos),
+                Err(e) => Msg::GetStatsErr(e)
+            }
+        });
+        apis::fetch(req, on_done).unwrap()
+    }
+
+    fn view_stats(&self, unnamed: &[String], named: &[NamedImage], hidden: &[String]) -> Html {
+        let named_set = named.iter().map(|n| &n.photo_filename).collect::<HashSet<_>>();
+        let named_photos = named_set.len();
+        // A photo named after being skipped counts as named only.
+        let skipped = self.state.skipped.iter().flatten().filter(|photo| !named_set.contains(photo)).collect::<HashSet<_>>();
+        let hidden = hidden.iter().filter(|photo| !named_set.contains(photo) && !skipped.contains(photo)).count();
+        let total = named_photos + unnamed.len() + skipped.len() + hidden;
+        let mut per_name = HashMap::<&String, usize>::new();
+        for n in named {
+            *per_name.entry(&n.name).or_default() += 1;
+        }
+        let mut per_name = per_name.into_iter().map(|(name, count)| (name.clone(), count)).collect::<Vec<_>>();
+        per_name.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then_with(|| a_name.cmp(b_name)));
+        let per_name = per_name.into_iter().map(|(name, count)| (name, count as f64)).collect::<Vec<_>>();
+        let throughput = sessions(named).into_iter().rev()
+            .map(|s| (format!("{} ({})", format_time(s.start), number(s.count as f64, 0)), s.photos_per_minute))
+            .collect::<Vec<_>>();
+        let named_ratio = if total > 0 { named_photos as f64 / total as f64 } else { 0.0 };
+        html!{<>
+            <h2>{t("stats.progress")}</h2>
+            <p>{tf("stats.summary", &[
+                ("total", number(total as f64, 0)),
+                ("named", number(named_photos as f64, 0)),
+                ("unnamed", number(unnamed.len() as f64, 0)),
+                ("skipped", number(skipped.len() as f64, 0)),
+                ("hidden", number(hidden as f64, 0)),
+                ("percent", number(named_ratio * 100.0, 1))
+            ])}</p>
+            <svg class="progress" width="600" height="20" viewBox="0 0 600 20">
+                <rect class="unnamed" x="0" y="0" width="600" height="20" />
+                <rect class="named" x="0" y="0" width={(named_ratio * 600.0).to_string()} height="20" />
+            </svg>
+            <h2>{t("stats.per_name")}</h2>
+            <BarChart bars={per_name} precision=0 />
+            <h2>{t("stats.throughput")}</h2>
+            <BarChart bars={throughput} />
+        </>}
+    }
+}
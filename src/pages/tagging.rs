@@ -1,43 +1,79 @@
+use std::collections::HashMap;
 use yew::prelude::*;
 use serde_json::to_string;
 use yew::services::fetch::*;
 use yew::format::*;
 use anyhow::Error;
+use yew_router::prelude::*;
+use yew_router::agent::RouteRequest;
+use crate::apis;
 use crate::components::*;
+use crate::live::*;
+use crate::query;
+use crate::route::Route;
 
 struct State {
     getting_tags: bool,
     get_tags_error: Option<Error>,
-    tags: Vec<String>
+    tags: Vec<String>,
+    avatars: HashMap<String, String>,
+    /// Position of the selected photo in the list, from the `pos` query parameter.
+    position: Option<usize>,
+    /// What the list shows, from the query parameters.
+    filter: ListFilter
 }
 
 pub struct TaggingPage {
     state: State,
+    props: Props,
     link: ComponentLink<Self>,
-    _task: Option<FetchTask>
+    router: RouteAgentDispatcher,
+    _live: Box<dyn Bridge<LiveAgent>>,
+    _task: Option<FetchTask>,
+    _avatars_task: Option<FetchTask>
+}
+
+#[derive(Clone, Properties)]
+pub struct Props {
+    /// The selected photo, from the URL.
+    #[prop_or_default]
+    pub photo: Option<String>
 }
 
 pub enum Msg {
-    ToTag(String),
+    ToTag((String, usize)),
+    Filter(ListFilter),
     GetTags,
     GetTagsOk(Vec<String>),
-    GetTagsErr(Error)
+    GetTagsErr(Error),
+    GetAvatars,
+    GetAvatarsOk(HashMap<String, String>),
+    Live(LiveEvent)
 }
 
 impl Component for TaggingPage {
     type Message = Msg;
-    type Properties = ();
+    type Properties = Props;
 
-    fn create(_: Self::Properties, link: ComponentLink<Self>) -> Self {
+    fn create(props: Self::Properties, link: ComponentLink<Self>) -> Self {
         link.send_message(Msg::GetTags);
+        link.send_message(Msg::GetAvatars);
+        let query = query::current();
         Self {
             state: State {
                 getting_tags: false,
                 get_tags_error: None,
-                tags: vec![]
+                tags: vec![],
+                avatars: HashMap::new(),
+                position: query.get("pos").and_then(|pos| pos.parse().ok()),
+                filter: ListFilter::from_query(&query)
             },
+            props,
+            _live: LiveAgent::bridge(link.callback(Msg::Live)),
             link,
-            _task: None
+            router: RouteAgentDispatcher::new(),
+            _task: None,
+            _avatars_task: None
         }
     }
 
@@ -45,7 +81,7 @@ impl Component for TaggingPage {
         match msg {
             Msg::GetTags => {
                 self.state.getting_tags = true;
-                let req = Request::get("/apis/names").body(Nothing).unwrap();
+                let req = apis::get("/apis/names").body(Nothing).unwrap();
                 let on_done = self.link.callback(move |response: Response<Json<Result<Vec<String>, Error>>>| {
                     let Json(data) = response.into_body();
                     match data {
@@ -53,7 +89,7 @@ impl Component for TaggingPage {
                         Err(e) => Msg::GetTagsErr(e)
                     }
                 });
-                let task = FetchService::fetch(req, on_done).unwrap();
+                let task = apis::fetch(req, on_done).unwrap();
                 self._task = Some(task);
                 true
             }
@@ -62,26 +98,52 @@ impl Component for TaggingPage {
                 self.state.tags = tags;
                 true
             }
+            Msg::Live(LiveEvent::NameCreated { name }) if !self.state.tags.contains(&name) => {
+                self.state.tags.push(name);
+                true
+            }
+            Msg::Live(_) => { false }
             Msg::GetTagsErr(e) => {
                 self.state.getting_tags = false;
                 self.state.get_tags_error = Some(e);
                 true
             }
-            Msg::ToTag(_filename) => {true} // TODO
+            Msg::GetAvatars => {
+                self._avatars_task = fetch_avatars(self.link.callback(Msg::GetAvatarsOk));
+                false
+            }
+            Msg::GetAvatarsOk(avatars) => {
+                self.state.avatars = avatars;
+                true
+            }
+            Msg::ToTag((filename, position)) => {
+                self.state.position = Some(position);
+                self.navigate(Some(&filename), false);
+                false
+            }
+            Msg::Filter(filter) => {
+                self.state.filter = filter;
+                let photo = self.props.photo.clone();
+                self.navigate(photo.as_deref(), true);
+                false
+            }
         }
     }
 
-    fn change(&mut self, _: Self::Properties) -> ShouldRender { true }
+    fn change(&mut self, props: Self::Properties) -> ShouldRender {
+        // Back and forward only change the URL, so the rest of the state is read from it again.
+        let query = query::current();
+        self.state.position = query.get("pos").and_then(|pos| pos.parse().ok());
+        self.state.filter = ListFilter::from_query(&query);
+        self.props = props;
+        true
+    }
 
     fn view(&self) -> Html { html!{<>
-<header> <h1>{"兼爱"}</h1> </header>
-<nav class="hnav"><ul>
-    <li><a href="/tagging">{"标注"}</a></li>
-    <li><a href="/new_tag">{"新名称"}</a></li>
-</ul></nav>
 <section>
     <nav>
-        <PhotoList onclick=self.link.callback(move |x| Msg::ToTag(x)) />
+        <PhotoList onclick=self.link.callback(Msg::ToTag) selected=self.props.photo.clone() position=self.state.position
+            filter=self.state.filter.clone() onfilter=self.link.callback(Msg::Filter) lease=true />
     </nav>
     <article>
         {if self.state.getting_tags {
@@ -89,9 +151,24 @@ impl Component for TaggingPage {
         } else {
             html!{}
         }}
-        <Tagging tags_json={to_string(&self.state.tags).unwrap()} />
+        <Tagging tags_json={to_string(&self.state.tags).unwrap()} avatars_json={to_string(&self.state.avatars).unwrap()} photo=self.props.photo.clone() />
     </article>
 </section>
-<footer>{"Magicloud"}</footer>
     </>} }
 }
+
+impl TaggingPage {
+    /// Puts the selection into the URL. Each viewed photo is a new history entry, so back and
+    /// forward walk through them; tweaking the list replaces the current entry instead.
+    fn navigate(&mut self, photo: Option<&str>, replace: bool) {
+        let path = match photo {
+            Some(photo) => Route::tagging_photo(photo),
+            None => Route::TaggingPage
+        };
+        let mut params = vec![("pos", self.state.position.map(|p| p.to_string()).unwrap_or_default())];
+        params.extend(self.state.filter.to_query());
+        let url = yew_router::route::Route::<()>::from(path).route + &query::build(&params);
+        let route = yew_router::route::Route::new_no_state(url);
+        self.router.send(if replace { RouteRequest::ReplaceRoute(route) } else { RouteRequest::ChangeRoute(route) });
+    }
+}
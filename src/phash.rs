@@ -0,0 +1,177 @@
+//! Perceptual hashes of photos, which stay close for the same picture resized, re-encoded or
+//! slightly edited, so that near-duplicates can be found without asking the server.
+
+use std::f64::consts::PI;
+use wasm_bindgen::{Clamped, JsCast};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement};
+
+/// The side of the grayscale square the hashes are computed from.
+const SIZE: usize = 32;
+/// Hashes differing in at most this many bits are taken for the same picture.
+const PHASH_THRESHOLD: u32 = 8;
+const DHASH_THRESHOLD: u32 = 10;
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct Hashes {
+    /// Which cells are brighter than the mean.
+    pub a: u64,
+    /// Which cells are brighter than their right neighbour.
+    pub d: u64,
+    /// Which low frequencies are stronger than their median.
+    pub p: u64
+}
+
+impl Hashes {
+    /// Reads the image back from a canvas, so it has to be loaded and of the same origin.
+    pub fn of_image(image: &HtmlImageElement) -> Option<Hashes> {
+        if !image.complete() || image.natural_width() == 0 {
+            return None;
+        }
+        let canvas = yew::utils::document().create_element("canvas").ok()?.dyn_into::<HtmlCanvasElement>().ok()?;
+        canvas.set_width(SIZE as u32);
+        canvas.set_height(SIZE as u32);
+        let context = canvas.get_context("2d").ok()??.dyn_into::<CanvasRenderingContext2d>().ok()?;
+        context.draw_image_with_html_image_element_and_dw_and_dh(image, 0.0, 0.0, SIZE as f64, SIZE as f64).ok()?;
+        let Clamped(rgba) = context.get_image_data(0.0, 0.0, SIZE as f64, SIZE as f64).ok()?.data();
+        Some(Hashes::of_gray(&gray(&rgba)))
+    }
+
+    fn of_gray(gray: &[f64]) -> Hashes {
+        let small = resize(gray, SIZE, SIZE, 8, 8);
+        let mean = small.iter().sum::<f64>() / small.len() as f64;
+        let a = bits(small.iter().map(|v| *v > mean));
+
+        let wide = resize(gray, SIZE, SIZE, 9, 8);
+        let d = bits((0..8).flat_map(|y| (0..8).map(move |x| (x, y))).map(|(x, y)| wide[y * 9 + x] > wide[y * 9 + x + 1]));
+
+        let dct = dct_8x8(gray);
+        // The first coefficient is just the overall brightness, which says nothing of the picture.
+        let mut ac = dct[1..].to_vec();
+        ac.sort_by(|x, y| x.partial_cmp(y).unwrap());
+        let median = ac[ac.len() / 2];
+        let p = bits(dct.iter().enumerate().map(|(i, v)| i > 0 && *v > median));
+
+        Hashes { a, d, p }
+    }
+
+    pub fn similar(&self, other: &Hashes) -> bool {
+        (self.p ^ other.p).count_ones() <= PHASH_THRESHOLD
+            && ((self.d ^ other.d).count_ones() <= DHASH_THRESHOLD || (self.a ^ other.a).count_ones() <= DHASH_THRESHOLD)
+    }
+}
+
+fn gray(rgba: &[u8]) -> Vec<f64> {
+    rgba.chunks(4)
+        .map(|px| 0.299 * px[0] as f64 + 0.587 * px[1] as f64 + 0.114 * px[2] as f64)
+        .collect()
+}
+
+/// Averages the pixels falling into each cell of the smaller grid.
+fn resize(pixels: &[f64], width: usize, height: usize, to_width: usize, to_height: usize) -> Vec<f64> {
+    let range = |i: usize, from: usize, to: usize| (i * from / to, ((i + 1) * from / to).max(i * from / to + 1));
+    let mut out = Vec::with_capacity(to_width * to_height);
+    for ty in 0..to_height {
+        let (y0, y1) = range(ty, height, to_height);
+        for tx in 0..to_width {
+            let (x0, x1) = range(tx, width, to_width);
+            let sum = (y0..y1).flat_map(|y| (x0..x1).map(move |x| pixels[y * width + x])).sum::<f64>();
+            out.push(sum / ((y1 - y0) * (x1 - x0)) as f64);
+        }
+    }
+    out
+}
+
+/// The lowest 8 × 8 frequencies of the two dimensional cosine transform of the square.
+fn dct_8x8(pixels: &[f64]) -> Vec<f64> {
+    let cos = (0..8).map(|u| (0..SIZE).map(|x| ((2 * x + 1) as f64 * u as f64 * PI / (2 * SIZE) as f64).cos()).collect::<Vec<_>>()).collect::<Vec<_>>();
+    let mut out = Vec::with_capacity(64);
+    for v in 0..8 {
+        for u in 0..8 {
+            let mut sum = 0.0;
+            for y in 0..SIZE {
+                for x in 0..SIZE {
+                    sum += pixels[y * SIZE + x] * cos[u][x] * cos[v][y];
+                }
+            }
+            out.push(sum);
+        }
+    }
+    out
+}
+
+fn bits(values: impl Iterator<Item = bool>) -> u64 {
+    values.take(64).enumerate().fold(0, |hash, (i, set)| if set { hash | 1 << i } else { hash })
+}
+
+/// Puts the photos into groups of near-duplicates, leaving out those without any. Every photo
+/// of a group is similar to every other one, so that a chain of look-alikes, each a little
+/// further from the first, does not end up as one group. Photos are taken in filename order,
+/// each joining the first group it fits in.
+pub fn groups<'a>(hashes: impl Iterator<Item = (&'a String, &'a Hashes)>) -> Vec<Vec<String>> {
+    let mut hashes = hashes.collect::<Vec<_>>();
+    hashes.sort_by_key(|(photo, _)| *photo);
+    let mut groups: Vec<Vec<(&String, &Hashes)>> = vec![];
+    for (photo, hash) in hashes {
+        match groups.iter_mut().find(|group| group.iter().all(|(_, other)| hash.similar(other))) {
+            Some(group) => group.push((photo, hash)),
+            None => groups.push(vec![(photo, hash)])
+        }
+    }
+    groups.into_iter()
+        .filter(|group| group.len() > 1)
+        .map(|group| group.into_iter().map(|(photo, _)| photo.clone()).collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(f: impl Fn(usize, usize) -> f64) -> Vec<f64> {
+        (0..SIZE).flat_map(|y| (0..SIZE).map(move |x| (x, y))).map(|(x, y)| f(x, y)).collect()
+    }
+
+    fn hashes(p: u64) -> Hashes {
+        Hashes { a: 0, d: 0, p }
+    }
+
+    #[test]
+    fn same_picture_brightened_or_noisy_is_similar() {
+        let picture = |x: usize, y: usize| ((x * 7 + y * 3) % 64) as f64 * 4.0;
+        let original = Hashes::of_gray(&image(picture));
+        let brighter = Hashes::of_gray(&image(|x, y| picture(x, y) + 20.0));
+        let noisy = Hashes::of_gray(&image(|x, y| picture(x, y) + ((x * 31 + y * 17) % 5) as f64));
+        assert!(original.similar(&brighter));
+        assert!(original.similar(&noisy));
+    }
+
+    #[test]
+    fn different_pictures_are_not_similar() {
+        let ramp = Hashes::of_gray(&image(|x, y| ((x * 7 + y * 3) % 64) as f64 * 4.0));
+        let blocks = Hashes::of_gray(&image(|x, y| if (x / 8 + y / 8) % 2 == 0 { 255.0 } else { 0.0 }));
+        assert!(!ramp.similar(&blocks));
+    }
+
+    #[test]
+    fn groups_leave_out_photos_without_duplicates() {
+        let (a, b) = ("a.jpg".to_string(), "b.jpg".to_string());
+        let (ha, hb) = (hashes(0), hashes(u64::MAX));
+        assert!(groups(vec![(&a, &ha), (&b, &hb)].into_iter()).is_empty());
+    }
+
+    #[test]
+    fn groups_do_not_chain_look_alikes() {
+        let (a, b, c) = ("a.jpg".to_string(), "b.jpg".to_string(), "c.jpg".to_string());
+        // b is within the threshold of both a and c, which are too far apart from each other.
+        let (ha, hb, hc) = (hashes(0), hashes(0b11111), hashes(0b11111_11111));
+        assert!(ha.similar(&hb) && hb.similar(&hc) && !ha.similar(&hc));
+        assert_eq!(groups(vec![(&c, &hc), (&b, &hb), (&a, &ha)].into_iter()), vec![vec![a.clone(), b.clone()]]);
+    }
+
+    #[test]
+    fn groups_hold_photos_all_similar_to_each_other() {
+        let (a, b, c) = ("a.jpg".to_string(), "b.jpg".to_string(), "c.jpg".to_string());
+        let (ha, hb, hc) = (hashes(0), hashes(0b1), hashes(0b11));
+        assert_eq!(groups(vec![(&b, &hb), (&a, &ha), (&c, &hc)].into_iter()), vec![vec![a, b, c]]);
+    }
+}
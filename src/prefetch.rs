@@ -0,0 +1,48 @@
+//! Loads and decodes the photos coming up next in the background, so that moving on to one of
+//! them shows it at once.
+
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+use web_sys::HtmlImageElement;
+use crate::route::encode_path;
+
+/// How many photos after the selected one are loaded ahead.
+pub const WINDOW: usize = 3;
+
+pub struct Prefetcher {
+    images: HashMap<String, HtmlImageElement>,
+    /// Prefetching is only a guess, so a photo failing to load or decode is of no concern here.
+    ignore_error: Closure<dyn FnMut(JsValue)>
+}
+
+impl Prefetcher {
+    pub fn new() -> Self {
+        Self {
+            images: HashMap::new(),
+            ignore_error: Closure::wrap(Box::new(|_| {}) as Box<dyn FnMut(JsValue)>)
+        }
+    }
+
+    /// Makes the given photos the ones loaded ahead, dropping those no longer among them.
+    pub fn prefetch(&mut self, photos: &[String]) {
+        self.images.retain(|photo, image| {
+            let keep = photos.contains(photo);
+            if !keep {
+                // Dropping the source cancels the download if it is still going on.
+                let _ = image.remove_attribute("src");
+            }
+            keep
+        });
+        for photo in photos {
+            if self.images.contains_key(photo) {
+                continue;
+            }
+            if let Ok(image) = HtmlImageElement::new() {
+                // The same URL as the viewer's, or the browser would not reuse what was loaded.
+                image.set_src(&format!("/pics/{}", encode_path(photo)));
+                let _ = image.decode().catch(&self.ignore_error);
+                self.images.insert(photo.clone(), image);
+            }
+        }
+    }
+}
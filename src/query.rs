@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
+
+/// Parses the query string of the current location.
+pub fn current() -> HashMap<String, String> {
+    let search = yew::utils::window().location().search().unwrap_or_default();
+    search.trim_start_matches('?').split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let decode = |s: &str| percent_decode_str(&s.replace('+', " ")).decode_utf8_lossy().to_string();
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+/// Builds a query string, including the leading `?`, leaving out empty values.
+pub fn build(params: &[(&str, String)]) -> String {
+    let query = params.iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(key, value)| format!("{}={}", key, utf8_percent_encode(value, NON_ALPHANUMERIC)))
+        .collect::<Vec<_>>()
+        .join("&");
+    if query.is_empty() { query } else { format!("?{}", query) }
+}
@@ -0,0 +1,128 @@
+//! Rotations the taggers made to photos the camera stored sideways, kept on the server so that
+//! every view of a photo shows it upright.
+
+use std::collections::HashMap;
+use yew::worker::*;
+use yew::agent::*;
+use yew::services::fetch::*;
+use yew::format::*;
+use anyhow::Error;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use crate::apis;
+use crate::event_buses::*;
+use crate::i18n::tf;
+
+pub enum RotationRequest {
+    /// Follows the rotation of the photo, instead of the one followed before.
+    Watch(String),
+    /// Turns the photo clockwise by the given degrees, a multiple of 90.
+    Rotate(String, u32)
+}
+
+pub enum Msg {
+    GetRotationsOk(HashMap<String, u32>),
+    Rotated(String),
+    RotateErr(String, u32)
+}
+
+pub struct RotationAgent {
+    link: AgentLink<RotationAgent>,
+    /// The photo each subscriber shows, which it is told the rotation of.
+    subscribers: HashMap<HandlerId, Option<String>>,
+    /// Clockwise degrees on top of what the EXIF orientation says.
+    rotations: HashMap<String, u32>,
+    event_bus_notification: Dispatcher<NotificationEventBus>,
+    _get_task: Option<FetchTask>,
+    rotate_tasks: HashMap<String, FetchTask>
+}
+
+impl RotationAgent {
+    fn set(&mut self, photo: String, degrees: u32) {
+        let degrees = degrees % 360;
+        self.rotations.insert(photo.clone(), degrees);
+        for (sub, _) in self.subscribers.iter().filter(|(_, watched)| watched.as_ref() == Some(&photo)) {
+            self.link.respond(*sub, (photo.clone(), degrees));
+        }
+    }
+}
+
+impl Agent for RotationAgent {
+    type Reach = Context<Self>;
+    type Message = Msg;
+    type Input = RotationRequest;
+    /// A photo and its rotation.
+    type Output = (String, u32);
+
+    fn create(link: AgentLink<Self>) -> Self {
+        let req = apis::get("/apis/rotations").body(Nothing).unwrap();
+        let on_done = link.callback(move |response: Response<Json<Result<HashMap<String, u32>, Error>>>| {
+            let Json(data) = response.into_body();
+            // Without the list the photos just show as the camera stored them.
+            Msg::GetRotationsOk(data.unwrap_or_default())
+        });
+        let task = apis::fetch(req, on_done).ok();
+        Self {
+            link,
+            subscribers: HashMap::new(),
+            rotations: HashMap::new(),
+            event_bus_notification: NotificationEventBus::dispatcher(),
+            _get_task: task,
+            rotate_tasks: HashMap::new()
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match msg {
+            Msg::GetRotationsOk(rotations) => {
+                for (photo, degrees) in rotations {
+                    self.set(photo, degrees);
+                }
+            }
+            Msg::Rotated(photo) => {
+                self.rotate_tasks.remove(&photo);
+            }
+            Msg::RotateErr(photo, previous) => {
+                self.rotate_tasks.remove(&photo);
+                self.event_bus_notification.send(Notification::Error(tf("rotation.failed", &[("photo", photo.clone())])));
+                self.set(photo, previous);
+            }
+        }
+    }
+
+    fn handle_input(&mut self, msg: Self::Input, id: HandlerId) {
+        match msg {
+            RotationRequest::Watch(photo) => {
+                self.link.respond(id, (photo.clone(), self.rotations.get(&photo).copied().unwrap_or(0)));
+                self.subscribers.insert(id, Some(photo));
+            }
+            RotationRequest::Rotate(photo, degrees) => {
+                let previous = self.rotations.get(&photo).copied().unwrap_or(0);
+                let rotated = (previous + degrees) % 360;
+                // Shown right away, and turned back if the server does not take it.
+                self.set(photo.clone(), rotated);
+                let req = apis::post(format!("/apis/rotate_image?photo_filename={}&degrees={}",
+                    utf8_percent_encode(&photo, NON_ALPHANUMERIC), rotated)).body(Nothing).unwrap();
+                let filename = photo.clone();
+                let on_done = self.link.callback(move |response: Response<Text>| {
+                    if response.status().is_success() {
+                        Msg::Rotated(filename.clone())
+                    } else {
+                        Msg::RotateErr(filename.clone(), previous)
+                    }
+                });
+                match apis::fetch(req, on_done) {
+                    Ok(task) => { self.rotate_tasks.insert(photo, task); }
+                    Err(_) => self.link.send_message(Msg::RotateErr(photo, previous))
+                }
+            }
+        }
+    }
+
+    fn connected(&mut self, id: HandlerId) {
+        self.subscribers.insert(id, None);
+    }
+
+    fn disconnected(&mut self, id: HandlerId) {
+        self.subscribers.remove(&id);
+    }
+}
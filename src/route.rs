@@ -1,9 +1,60 @@
 use yew_router::prelude::*;
+use yew_router::switch::Permissive;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use crate::session::{Role, Session};
 
-#[derive(Switch, Debug, Clone)]
+/// Filenames keep their directory separators in the path.
+const PATH: &AsciiSet = &NON_ALPHANUMERIC.remove(b'/').remove(b'.').remove(b'-').remove(b'_');
+
+#[derive(Switch, Debug, Clone, PartialEq)]
 pub enum Route {
+    /// The filename is percent-encoded.
+    #[to = "/tagging/{*:filename}"]
+    TaggingPhoto(String),
     #[to = "/tagging"]
     TaggingPage,
     #[to = "/new_tag"]
     NewTagPage,
+    #[to = "/stats"]
+    StatsPage,
+    #[to = "/skipped"]
+    SkippedPage,
+    #[to = "/export"]
+    ExportPage,
+    #[to = "/hidden"]
+    HiddenPage,
+    #[to = "/login"]
+    LoginPage,
+    #[to = "/404"]
+    NotFound(Permissive<String>),
+    #[to = "/!"]
+    Home,
+}
+
+
+impl Route {
+    /// The least role needed to open the route, or `None` if anyone can.
+    pub fn required_role(&self) -> Option<Role> {
+        match self {
+            Route::Home | Route::LoginPage | Route::NotFound(_) => None,
+            Route::TaggingPage | Route::TaggingPhoto(_) => Some(Role::Tagger),
+            Route::NewTagPage | Route::StatsPage | Route::SkippedPage | Route::HiddenPage | Route::ExportPage => Some(Role::Admin)
+        }
+    }
+
+    pub fn allowed(&self, session: &Session) -> bool {
+        match self.required_role() {
+            None => true,
+            Some(required) => session.role().map_or(false, |role| role >= required)
+        }
+    }
+
+    pub fn tagging_photo(filename: &str) -> Route {
+        Route::TaggingPhoto(encode_path(filename))
+    }
+}
+
+/// Percent-encodes each segment of a photo's path, for putting it into a URL.
+pub fn encode_path(filename: &str) -> String {
+    utf8_percent_encode(filename, PATH).to_string()
 }
@@ -0,0 +1,287 @@
+use std::collections::{HashMap, HashSet};
+use serde::Deserialize;
+use yew::worker::*;
+use yew::agent::*;
+use yew::services::fetch::*;
+use yew::format::*;
+use anyhow::{anyhow, Error};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use crate::apis;
+use crate::event_buses::*;
+use crate::i18n::tf;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum UpdateStatus {
+    NotYet,
+    Doing,
+    Failed,
+    Succeeded,
+    Skipped,
+    Hidden,
+    /// Someone else named the photo first, and the tagger has to decide what to do.
+    Conflict
+}
+impl ToString for UpdateStatus {
+    fn to_string(&self) -> String {
+        match self {
+            UpdateStatus::NotYet => {"notyet"}
+            UpdateStatus::Doing => {"doing"}
+            UpdateStatus::Failed => {"failed"}
+            UpdateStatus::Succeeded => {"succeeded"}
+            UpdateStatus::Skipped => {"skipped"}
+            UpdateStatus::Hidden => {"hidden"}
+            UpdateStatus::Conflict => {"conflict"}
+        }.to_string()
+    }
+}
+
+/// What the server reports when a photo has been named since the tagger last saw it.
+#[derive(Deserialize, Clone, PartialEq)]
+pub struct Conflict {
+    pub names: Vec<String>,
+    pub by: String,
+    /// The version of the names, so that replacing or adding to them fails again if they change once more.
+    pub version: String
+}
+
+#[derive(Clone, Copy)]
+pub enum Resolution {
+    /// Leaves the names given by the other tagger.
+    Keep,
+    Replace,
+    /// Gives the photo this name besides the existing ones.
+    Add
+}
+impl Resolution {
+    /// How the name is sent again, unless nothing is to be sent.
+    fn mode(&self) -> Option<&'static str> {
+        match self {
+            Resolution::Keep => None,
+            Resolution::Replace => Some("replace"),
+            Resolution::Add => Some("add")
+        }
+    }
+}
+
+pub enum SaveQueueRequest {
+    Enqueue(String, Decision),
+    RetryFailed,
+    Resolve(String, Resolution)
+}
+
+#[derive(Clone)]
+pub enum SaveQueueOutput {
+    /// The status of the job for a photo.
+    Status(String, UpdateStatus),
+    Conflict(String, Conflict),
+    Pending {
+        doing: usize,
+        failed: usize
+    }
+}
+
+pub enum Msg {
+    Done(String, Result<(), Error>),
+    Conflict(String, Conflict)
+}
+
+struct Job {
+    decision: Decision,
+    status: UpdateStatus,
+    conflict: Option<Conflict>,
+    /// The version the tagger chose to override, and the mode of doing so.
+    resolution: Option<(String, &'static str)>,
+    task: Option<FetchTask>
+}
+
+/// Sends the decisions on photos to the server. Living in an agent instead of the photo
+/// components, the requests survive navigating away from the page they were made on.
+pub struct SaveQueue {
+    link: AgentLink<SaveQueue>,
+    subscribers: HashSet<HandlerId>,
+    jobs: HashMap<String, Job>,
+    event_bus_session: Dispatcher<SessionEventBus>,
+    event_bus_notification: Dispatcher<NotificationEventBus>
+}
+
+impl SaveQueue {
+    fn broadcast(&self, output: SaveQueueOutput) {
+        for sub in self.subscribers.iter() {
+            self.link.respond(*sub, output.clone());
+        }
+    }
+
+    fn pending(&self) -> SaveQueueOutput {
+        SaveQueueOutput::Pending {
+            doing: self.jobs.values().filter(|job| job.status == UpdateStatus::Doing).count(),
+            failed: self.jobs.values().filter(|job| job.status == UpdateStatus::Failed).count()
+        }
+    }
+
+    fn start(&mut self, photo: String) {
+        let job = &self.jobs[&photo];
+        let req = match (&job.decision, &job.resolution) {
+            // Naming is conditional, so that it cannot silently overwrite what someone else did meanwhile.
+            (Decision::Name(tag), None) => apis::post(format!("/apis/name_image?photo_filename={}&name={}",
+                    utf8_percent_encode(&photo, NON_ALPHANUMERIC), utf8_percent_encode(tag, NON_ALPHANUMERIC)))
+                .header("If-None-Match", "*"),
+            (Decision::Name(tag), Some((version, resolution))) => apis::post(format!("/apis/name_image?photo_filename={}&name={}&mode={}",
+                    utf8_percent_encode(&photo, NON_ALPHANUMERIC), utf8_percent_encode(tag, NON_ALPHANUMERIC), resolution))
+                .header("If-Match", format!("\"{}\"", version)),
+            (Decision::Skip(reason), _) => apis::post(format!("/apis/skip_image?photo_filename={}&reason={}",
+                utf8_percent_encode(&photo, NON_ALPHANUMERIC), reason.to_string())),
+            (Decision::Hide, _) => apis::post(format!("/apis/hide_image?photo_filename={}",
+                utf8_percent_encode(&photo, NON_ALPHANUMERIC)))
+        }.body(Nothing).unwrap();
+        let filename = photo.clone();
+        let on_done = self.link.callback(move |response: Response<Result<String, Error>>| {
+            if response.status().is_success() {
+                Msg::Done(filename.clone(), Ok(()))
+            } else if response.status() == StatusCode::CONFLICT || response.status() == StatusCode::PRECONDITION_FAILED {
+                match response.body().as_ref().map(|body| serde_json::from_str::<Conflict>(body)) {
+                    Ok(Ok(conflict)) => Msg::Conflict(filename.clone(), conflict),
+                    _ => Msg::Done(filename.clone(), Err(anyhow!(tf("errors.status", &[("status", response.status().to_string())]))))
+                }
+            } else {
+                Msg::Done(filename.clone(), Err(anyhow!(tf("errors.status", &[("status", response.status().to_string())]))))
+            }
+        });
+        let task = apis::fetch(req, on_done);
+        let job = self.jobs.get_mut(&photo).unwrap();
+        job.status = UpdateStatus::Doing;
+        match task {
+            Ok(task) => job.task = Some(task),
+            Err(e) => self.link.send_message(Msg::Done(photo.clone(), Err(e)))
+        }
+        self.broadcast(SaveQueueOutput::Status(photo, UpdateStatus::Doing));
+        self.broadcast(self.pending());
+    }
+}
+
+impl Agent for SaveQueue {
+    type Reach = Context<Self>;
+    type Message = Msg;
+    type Input = SaveQueueRequest;
+    type Output = SaveQueueOutput;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        Self {
+            link,
+            subscribers: HashSet::new(),
+            jobs: HashMap::new(),
+            event_bus_session: SessionEventBus::dispatcher(),
+            event_bus_notification: NotificationEventBus::dispatcher()
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match msg {
+            Msg::Done(photo, result) => {
+                let job = match self.jobs.get_mut(&photo) {
+                    Some(job) => job,
+                    None => return
+                };
+                job.task = None;
+                job.status = match (&result, &job.decision) {
+                    (Ok(()), Decision::Name(_)) => {
+                        self.event_bus_session.send(SessionEvents::Saved);
+                        UpdateStatus::Succeeded
+                    }
+                    (Ok(()), Decision::Skip(_)) => UpdateStatus::Skipped,
+                    (Ok(()), Decision::Hide) => UpdateStatus::Hidden,
+                    (Err(e), _) => {
+                        self.event_bus_session.send(SessionEvents::SaveFailed);
+                        self.event_bus_notification.send(Notification::Error(tf("save_queue.failed", &[("photo", photo.clone()), ("error", e.to_string())])));
+                        UpdateStatus::Failed
+                    }
+                };
+                let status = job.status;
+                self.broadcast(SaveQueueOutput::Status(photo, status));
+                self.broadcast(self.pending());
+            }
+            Msg::Conflict(photo, conflict) => {
+                let job = match self.jobs.get_mut(&photo) {
+                    Some(job) => job,
+                    None => return
+                };
+                job.task = None;
+                job.status = UpdateStatus::Conflict;
+                job.conflict = Some(conflict.clone());
+                self.event_bus_notification.send(Notification::Error(tf("save_queue.conflict", &[
+                    ("photo", photo.clone()),
+                    ("names", conflict.names.join(", ")),
+                    ("by", conflict.by.clone())
+                ])));
+                self.broadcast(SaveQueueOutput::Status(photo.clone(), UpdateStatus::Conflict));
+                self.broadcast(SaveQueueOutput::Conflict(photo, conflict));
+                self.broadcast(self.pending());
+            }
+        }
+    }
+
+    fn handle_input(&mut self, msg: Self::Input, _id: HandlerId) {
+        match msg {
+            SaveQueueRequest::Enqueue(photo, decision) => {
+                match &decision {
+                    Decision::Name(_) => self.event_bus_session.send(SessionEvents::Saving),
+                    Decision::Skip(_) | Decision::Hide => self.event_bus_session.send(SessionEvents::Skipped)
+                }
+                self.jobs.insert(photo.clone(), Job {
+                    decision,
+                    status: UpdateStatus::NotYet,
+                    conflict: None,
+                    resolution: None,
+                    task: None
+                });
+                self.start(photo);
+            }
+            SaveQueueRequest::RetryFailed => {
+                let failed = self.jobs.iter()
+                    .filter(|(_, job)| job.status == UpdateStatus::Failed)
+                    .map(|(photo, _)| photo.clone())
+                    .collect::<Vec<_>>();
+                for photo in failed {
+                    self.start(photo);
+                }
+            }
+            SaveQueueRequest::Resolve(photo, resolution) => {
+                let job = match self.jobs.get_mut(&photo) {
+                    Some(job) => job,
+                    None => return
+                };
+                let conflict = match job.conflict.take() {
+                    Some(conflict) => conflict,
+                    None => return
+                };
+                match resolution.mode() {
+                    None => {
+                        // The photo is named all the same, just not by this tagger.
+                        job.status = UpdateStatus::Succeeded;
+                        self.event_bus_session.send(SessionEvents::Saved);
+                        self.broadcast(SaveQueueOutput::Status(photo, UpdateStatus::Succeeded));
+                        self.broadcast(self.pending());
+                    }
+                    Some(mode) => {
+                        job.resolution = Some((conflict.version, mode));
+                        self.start(photo);
+                    }
+                }
+            }
+        }
+    }
+
+    fn connected(&mut self, id: HandlerId) {
+        self.subscribers.insert(id);
+        for (photo, job) in self.jobs.iter() {
+            self.link.respond(id, SaveQueueOutput::Status(photo.clone(), job.status));
+            if let Some(conflict) = &job.conflict {
+                self.link.respond(id, SaveQueueOutput::Conflict(photo.clone(), conflict.clone()));
+            }
+        }
+        self.link.respond(id, self.pending());
+    }
+
+    fn disconnected(&mut self, id: HandlerId) {
+        self.subscribers.remove(&id);
+    }
+}
@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+use serde::Deserialize;
+use yew::worker::*;
+use yew::format::*;
+use yew::services::fetch::*;
+use anyhow::Error;
+use crate::apis;
+
+/// Roles are ordered, each one allowed everything the ones before it are.
+#[derive(Deserialize, Clone, Copy, PartialEq, PartialOrd)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Tagger,
+    Admin
+}
+
+#[derive(Deserialize, Clone, PartialEq)]
+pub struct User {
+    pub name: String,
+    pub role: Role
+}
+
+#[derive(Clone, PartialEq)]
+pub enum Session {
+    /// Still asking the server who is logged in.
+    Pending,
+    LoggedOut,
+    LoggedIn(User)
+}
+
+impl Session {
+    pub fn role(&self) -> Option<Role> {
+        match self {
+            Session::LoggedIn(user) => Some(user.role),
+            _ => None
+        }
+    }
+}
+
+pub enum SessionRequest {
+    /// The user logged in with the token stored by the login page.
+    LoggedIn(User),
+    LoggedOut,
+    /// The server rejected the token.
+    Expired
+}
+
+pub enum Msg {
+    GetMeOk(User),
+    GetMeErr
+}
+
+/// Holds who is logged in, and tells every subscriber whenever that changes.
+pub struct SessionAgent {
+    link: AgentLink<SessionAgent>,
+    subscribers: HashSet<HandlerId>,
+    session: Session,
+    _task: Option<FetchTask>
+}
+
+impl SessionAgent {
+    fn set_session(&mut self, session: Session) {
+        self.session = session;
+        for sub in self.subscribers.iter() {
+            self.link.respond(*sub, self.session.clone());
+        }
+    }
+}
+
+impl Agent for SessionAgent {
+    type Reach = Context<Self>;
+    type Message = Msg;
+    type Input = SessionRequest;
+    type Output = Session;
+
+    fn create(link: AgentLink<Self>) -> Self {
+        // Asked even without a token, as the server may know the user some other way.
+        let req = apis::get("/apis/me").body(Nothing).unwrap();
+        let on_done = link.callback(move |response: Response<Json<Result<User, Error>>>| {
+            let Json(data) = response.into_body();
+            match data {
+                Ok(user) => Msg::GetMeOk(user),
+                Err(_) => Msg::GetMeErr
+            }
+        });
+        let task = apis::fetch(req, on_done).ok();
+        Self {
+            link,
+            subscribers: HashSet::new(),
+            session: if task.is_some() { Session::Pending } else { Session::LoggedOut },
+            _task: task
+        }
+    }
+
+    fn update(&mut self, msg: Self::Message) {
+        match msg {
+            Msg::GetMeOk(user) => self.set_session(Session::LoggedIn(user)),
+            Msg::GetMeErr => self.set_session(Session::LoggedOut)
+        }
+    }
+
+    fn handle_input(&mut self, msg: Self::Input, _id: HandlerId) {
+        match msg {
+            SessionRequest::LoggedIn(user) => self.set_session(Session::LoggedIn(user)),
+            SessionRequest::LoggedOut | SessionRequest::Expired => self.set_session(Session::LoggedOut)
+        }
+    }
+
+    fn connected(&mut self, id: HandlerId) {
+        self.subscribers.insert(id);
+        self.link.respond(id, self.session.clone());
+    }
+
+    fn disconnected(&mut self, id: HandlerId) {
+        self.subscribers.remove(&id);
+    }
+}
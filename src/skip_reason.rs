@@ -0,0 +1,28 @@
+/// Why a photo was skipped instead of named. Persisted server side so skipped photos
+/// stay out of the unnamed list and can be reviewed separately.
+#[derive(Clone, Copy, PartialEq)]
+pub enum SkipReason {
+    NoFace,
+    Unrecognizable,
+    UnknownPerson,
+    BadPhoto
+}
+
+impl SkipReason {
+    pub const ALL: [SkipReason; 4] = [SkipReason::NoFace, SkipReason::Unrecognizable, SkipReason::UnknownPerson, SkipReason::BadPhoto];
+
+    pub fn label(&self) -> String {
+        crate::i18n::t(&format!("skip.{}", self.to_string()))
+    }
+}
+
+impl ToString for SkipReason {
+    fn to_string(&self) -> String {
+        match self {
+            SkipReason::NoFace => {"no_face"}
+            SkipReason::Unrecognizable => {"unrecognizable"}
+            SkipReason::UnknownPerson => {"unknown_person"}
+            SkipReason::BadPhoto => {"bad_photo"}
+        }.to_string()
+    }
+}